@@ -1,7 +1,10 @@
 use amethyst::renderer::{
     camera::CameraPrefab,
     light::LightPrefab,
-    sprite::prefab::{SpriteRenderPrefab, SpriteSheetPrefab},
+    sprite::{
+        prefab::{SpriteRenderPrefab, SpriteSheetPrefab},
+        SpriteSheet,
+    },
     transparent::Transparent,
 };
 use amethyst::{
@@ -12,6 +15,7 @@ use amethyst::{
     Error,
 };
 
+use crate::assets::ResourceCollection;
 use crate::components as c;
 use amethyst::utils::application_root_dir;
 use serde::{Deserialize, Serialize};
@@ -32,6 +36,12 @@ pub struct EntityPrefabData {
     light: Option<LightPrefab>,
     player: Option<c::Player>,
     controller: Option<c::Controller>,
+    engine: Option<c::Engine>,
+    health: Option<c::Health>,
+    hull: Option<c::Hull>,
+    faction: Option<c::Faction>,
+    hardpoints: Option<c::Hardpoints>,
+    inventory: Option<c::Inventory>,
     weapon_manager: Option<c::weapon::WeaponManager>,
 }
 
@@ -135,3 +145,39 @@ pub fn update_prefabs(world: &mut World) {
         .write_resource::<EntityPrefabs>()
         .set_prefabs(updated_prefabs);
 }
+
+// Weapons name the sprite their projectiles use. Look every one of them up as soon as the prefabs
+// are loaded, so a bad reference fails here instead of the first time the weapon fires.
+pub fn resolve_projectile_sprites(world: &mut World) -> Result<(), Error> {
+    let resources = world.read_resource::<ResourceCollection>();
+    let sheets = world.read_resource::<AssetStorage<SpriteSheet>>();
+    let entity_prefabs = world.read_resource::<EntityPrefabs>();
+    let mut prefab_resource = world.write_resource::<AssetStorage<Prefab<EntityPrefabData>>>();
+
+    for (key, handle) in entity_prefabs.get_prefabs().iter() {
+        if let Some(prefab) = prefab_resource.get_mut(handle) {
+            for index in 0..prefab.len() {
+                let manager = prefab
+                    .entity(index)
+                    .and_then(|entity| entity.data_mut())
+                    .and_then(|data| data.weapon_manager.as_mut());
+                if let Some(manager) = manager {
+                    for weapon in manager.weapons_mut() {
+                        let name = weapon.name().to_string();
+                        let sprite = &mut weapon.projectile.sprite;
+                        let render = resources
+                            .sprite_render(&sprite.sheet, &sprite.sprite, &sheets)
+                            .map_err(|e| {
+                                Error::from_string(format!(
+                                    "Weapon \"{}\" in prefab \"{}\": {}",
+                                    name, key, e
+                                ))
+                            })?;
+                        sprite.render = Some(render);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,32 +1,49 @@
 use amethyst::{
     assets::{AssetStorage, Handle, Loader, ProgressCounter, RonFormat},
+    config::Config,
     prelude::*,
     renderer::{
         formats::texture::ImageFormat,
         sprite::{SpriteSheet, SpriteSheetFormat},
-        Texture,
+        SpriteRender, Texture,
     },
     ecs::{ReadExpect, Read},
+    Error,
 };
 
 use crate::assets::prefab::{initialize_prefabs, update_prefabs};
+use crate::components::weapon::SpriteId;
 use crate::resources::SResource;
 use crate::states::main::MainGameState;
 use amethyst::utils::application_root_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::read_dir;
 use walkdir::WalkDir;
 
-const PROJECTILES_PATH: &'static str = "textures/projectiles";
+const SPRITE_SHEETS_CONFIG: &'static str = "resources/config/sprite_sheets.ron";
 const PREFABS_PATH: &'static str = "resources/prefabs";
 
+/// A sprite sheet and the names of its sprites, as listed in `config/sprite_sheets.ron`
+#[derive(Deserialize, Serialize, Default)]
+pub struct SpriteSheetConfig {
+    /// Path of the sheet's `.png` and `.ron` files, without extension, relative to the assets folder
+    pub path: String,
+    /// Sprite indices by name
+    #[serde(default)]
+    pub sprites: HashMap<String, usize>,
+}
+
 #[derive(Default)]
 pub struct ResourceCollection {
-    pub projectile_sheet: Option<Handle<SpriteSheet>>,
+    pub sprite_sheets: SResource<SpriteSheet>,
+    /// Sprite indices by name for every sheet
+    sprite_names: HashMap<String, HashMap<String, usize>>,
 }
 
 impl ResourceCollection {
     pub fn build(&mut self, world: &mut World, progress: ProgressCounter) -> ProgressCounter {
-        let progress = self.register_projectile_sheets(world, progress);
+        let progress = self.register_sprite_sheets(world, progress);
         progress
     }
 
@@ -34,28 +51,80 @@ impl ResourceCollection {
         world.add_resource(self);
     }
 
-    fn register_projectile_sheets(&mut self, world: &mut World, mut progress: ProgressCounter) -> ProgressCounter {
+    /// Finds a sprite by sheet name and sprite name or index, failing if either doesn't exist
+    pub fn sprite_render(
+        &self,
+        sheet_name: &str,
+        sprite: &SpriteId,
+        sheets: &AssetStorage<SpriteSheet>,
+    ) -> Result<SpriteRender, Error> {
+        let handle = self
+            .sprite_sheets
+            .get(sheet_name.to_string())
+            .ok_or_else(|| Error::from_string(format!("Unknown sprite sheet \"{}\"", sheet_name)))?;
+
+        let sprite_number = match sprite {
+            SpriteId::Index(index) => *index,
+            SpriteId::Name(name) => *self
+                .sprite_names
+                .get(sheet_name)
+                .and_then(|names| names.get(name))
+                .ok_or_else(|| {
+                    Error::from_string(format!(
+                        "Unknown sprite \"{}\" in sprite sheet \"{}\"",
+                        name, sheet_name
+                    ))
+                })?,
+        };
+
+        // Make sure the index actually points at a sprite if the sheet has finished loading
+        if let Some(sheet) = sheets.get(handle) {
+            if sprite_number >= sheet.sprites.len() {
+                return Err(Error::from_string(format!(
+                    "Sprite {} is out of range for sprite sheet \"{}\" with {} sprites",
+                    sprite_number,
+                    sheet_name,
+                    sheet.sprites.len()
+                )));
+            }
+        }
+
+        Ok(SpriteRender {
+            sprite_sheet: handle.clone(),
+            sprite_number,
+        })
+    }
+
+    fn register_sprite_sheets(&mut self, world: &mut World, mut progress: ProgressCounter) -> ProgressCounter {
         type SData<'s> = (
             ReadExpect<'s, Loader>,
             Read<'s, AssetStorage<Texture>>,
             Read<'s, AssetStorage<SpriteSheet>>,
         );
-        self.projectile_sheet = Some(world.exec(|(loader, textures, sprite_sheets): SData| {
-            let texture_handle = { 
+        let config_path = application_root_dir().unwrap().join(SPRITE_SHEETS_CONFIG);
+        let configs = HashMap::<String, SpriteSheetConfig>::load_no_fallback(&config_path)
+            .expect(&format!("Error loading sprite sheets from {:?}", config_path));
+
+        for (name, config) in configs {
+            let handle = world.exec(|(loader, textures, sprite_sheets): SData| {
+                let texture_handle = {
+                    loader.load(
+                        format!("{}.{}", config.path, "png"),
+                        ImageFormat::default(),
+                        &mut progress,
+                        &textures,
+                    )
+                };
                 loader.load(
-                    format!("{}.{}", PROJECTILES_PATH, "png"),
-                    ImageFormat::default(),
+                    format!("{}.{}", config.path, "ron"),
+                    SpriteSheetFormat(texture_handle),
                     &mut progress,
-                    &textures,
+                    &sprite_sheets,
                 )
-            };
-            loader.load(
-                format!("{}.{}", PROJECTILES_PATH, "ron"),
-                SpriteSheetFormat(texture_handle),
-                &mut progress,
-                &sprite_sheets,
-            )
-        }));
+            });
+            self.sprite_sheets.insert(name.clone(), handle);
+            self.sprite_names.insert(name, config.sprites);
+        }
         progress
     }
 }
@@ -1,9 +1,12 @@
 //! Controls and stores data for a movement
-use crate::components::weapon::Projectile;
+use crate::components::{
+    weapon::{Inheritance, Launch, Projectile},
+    Engine, Hull,
+};
 use amethyst::{
     assets::PrefabData,
     core::{
-        math::{Unit, Vector3},
+        math::{Unit, UnitQuaternion, Vector3},
         Float,
     },
     derive::PrefabData,
@@ -21,11 +24,63 @@ pub struct Controller {
     pub velocity: Vector3<Float>,
     pub rotation_control: Float,
     pub thrust_control: Float,
+    /// Sideways thrust, from -1 (left) to 1 (right)
+    pub strafe_control: Float,
     pub turn_speed: Float,
+    /// Current turning speed in radians per second, counter-clockwise
+    pub angular_velocity: Float,
+    /// How quickly turning speeds up. `0` turns at full `turn_speed` instantly.
+    pub angular_acceleration: Float,
+    /// Fraction of angular velocity lost every second, so the ship stops spinning on its own
+    pub angular_damping: Float,
     pub traction: Float,
+    /// Acceleration of the lateral thrusters at full strafe
+    pub strafe_traction: Float,
     pub max_speed: Float,
     pub min_speed: Float,
     pub start_speed: Float,
+    /// How the ship's velocity behaves on its own, between thrusts
+    pub flight_model: FlightModel,
+    /// Turns the `FlightModel::Assisted` drift correction on and off. With it off, the ship flies
+    /// like `FlightModel::Newtonian`. Drift is also left alone while strafing.
+    pub flight_assist: bool,
+    /// Set while the pilot wants the engine's afterburner
+    #[serde(skip)]
+    pub boosting: bool,
+}
+
+/// How velocity changes when nothing is pushing the ship
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum FlightModel {
+    /// Velocity is kept forever until thrust changes it
+    Newtonian,
+    /// Drag slows the ship down whenever it moves
+    Arcade(Drag),
+    /// Sideways drift is cancelled out, so the ship goes where it is pointed. `strength` is how
+    /// much of the drift is removed per second.
+    Assisted { strength: Float },
+}
+
+/// Slows a moving ship down
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Drag {
+    /// The same speed is lost every second, so the ship comes to a full stop
+    Constant(Float),
+    /// Deceleration grows with speed
+    Linear(Float),
+    /// Deceleration grows with the square of speed, so it mostly holds back fast ships
+    Quadratic(Float),
+}
+
+impl Drag {
+    /// How much speed is lost per second when moving at `speed`
+    pub fn deceleration(self, speed: Float) -> Float {
+        match self {
+            Drag::Constant(deceleration) => deceleration,
+            Drag::Linear(factor) => factor * speed,
+            Drag::Quadratic(factor) => factor * speed * speed,
+        }
+    }
 }
 
 impl Default for Controller {
@@ -34,11 +89,19 @@ impl Default for Controller {
             velocity: Vector3::zeros(),
             rotation_control: Float::from(0.0),
             thrust_control: Float::from(0.0),
+            strafe_control: Float::from(0.0),
             turn_speed: Float::from(1.0),
+            angular_velocity: Float::from(0.0),
+            angular_acceleration: Float::from(0.0),
+            angular_damping: Float::from(0.0),
             traction: Float::from(0.05),
+            strafe_traction: Float::from(0.0),
             max_speed: Float::from(10.0),
             min_speed: Float::from(0.0),
             start_speed: Float::from(0.0),
+            flight_model: FlightModel::Newtonian,
+            flight_assist: true,
+            boosting: false,
         }
     }
 }
@@ -48,15 +111,249 @@ impl Component for Controller {
 }
 
 impl Controller {
-    /// When we fire, we want to impart data from our projectile onto our controller
-    pub fn set_from_projectile(mut self, projectile: &Projectile) -> Self {
+    /// When we fire, we want to impart data from our projectile onto our controller. `rotation` is the
+    /// direction the projectile is fired in.
+    pub fn set_from_projectile(
+        mut self,
+        projectile: &Projectile,
+        rotation: &UnitQuaternion<Float>,
+    ) -> Self {
+        let direction = rotation * Vector3::y();
+        let ship_speed = self.velocity.magnitude();
+        self.velocity = match projectile.inheritance {
+            Inheritance::None => Vector3::zeros(),
+            Inheritance::Full => self.velocity,
+            Inheritance::Forward => direction.scale(self.velocity.dot(&direction)),
+            Inheritance::Scale(factor) => self.velocity.scale(factor),
+        };
         self.max_speed = projectile.max_speed;
         self.traction = projectile.traction;
         self.min_speed = projectile.initial_speed;
-        self.start_speed = self.velocity.magnitude();
+        self.start_speed = match projectile.launch {
+            Launch::ShipSpeed => ship_speed,
+            Launch::InitialSpeed => projectile.initial_speed,
+        };
         // Projectile should travel along continuously
         self.thrust_control = Float::from(1.0);
         self.rotation_control = Float::from(0.0);
+        self.strafe_control = Float::from(0.0);
+        self.boosting = false;
+        // Homing projectiles steer instantly
+        self.angular_velocity = Float::from(0.0);
+        self.angular_acceleration = Float::from(0.0);
+        // Projectiles keep flying the way they were fired, whatever the ship's flight model
+        self.flight_model = FlightModel::Newtonian;
+        // Homing projectiles steer with their own turn rate
+        if let Some(homing) = &projectile.homing {
+            self.turn_speed = homing.turn_rate;
+        }
         self
     }
+
+    /// Like `set_from_projectile`, but the projectile is dropped in place with no thrust of its own.
+    /// It drifts with whatever velocity it inherits and brakes at its `traction` until it stops.
+    pub fn set_from_mine(self, projectile: &Projectile, rotation: &UnitQuaternion<Float>) -> Self {
+        let mut mine = self.set_from_projectile(projectile, rotation);
+        mine.thrust_control = Float::from(0.0);
+        mine.start_speed = Float::from(0.0);
+        mine.min_speed = Float::from(0.0);
+        mine.flight_model = FlightModel::Arcade(Drag::Constant(projectile.traction));
+        mine
+    }
+
+    /// Updates the angular velocity from `rotation_control` and returns how far to turn over
+    /// `delta_seconds`
+    pub fn turn(&mut self, delta_seconds: Float) -> Float {
+        if self.angular_acceleration <= Float::from(0.0) {
+            self.angular_velocity = self.rotation_control * self.turn_speed;
+        } else {
+            self.angular_velocity +=
+                self.rotation_control * self.angular_acceleration * delta_seconds;
+            let damping = (self.angular_damping * delta_seconds).as_f32().min(1.0);
+            self.angular_velocity -= self.angular_velocity * Float::from(damping);
+            // Never spin faster than the engine allows
+            let limit = self.turn_speed.as_f32().abs();
+            let clamped = self.angular_velocity.as_f32().max(-limit).min(limit);
+            self.angular_velocity = Float::from(clamped);
+        }
+        self.angular_velocity * delta_seconds
+    }
+
+    /// Applies drag or flight assist for `delta_seconds`. `forward` is the direction the ship faces.
+    pub fn apply_flight_model(&mut self, forward: &Vector3<Float>, delta_seconds: Float) {
+        match self.flight_model {
+            FlightModel::Newtonian => {}
+            FlightModel::Arcade(drag) => {
+                let speed = self.velocity.magnitude();
+                if speed > Float::from(0.0) {
+                    // Drag can stop the ship, but never push it backwards
+                    let slowed = (speed - drag.deceleration(speed) * delta_seconds).as_f32();
+                    self.velocity = self.velocity.scale(Float::from(slowed.max(0.0)) / speed);
+                }
+            }
+            FlightModel::Assisted { strength }
+                if self.flight_assist && self.strafe_control == Float::from(0.0) =>
+            {
+                let drift = self.velocity - forward.scale(self.velocity.dot(forward));
+                let correction = (strength * delta_seconds).as_f32().min(1.0);
+                self.velocity -= drift.scale(Float::from(correction));
+            }
+            FlightModel::Assisted { .. } => {}
+        }
+    }
+
+    /// Derives movement stats from a ship's engine and hull. Heavier hulls accelerate and turn
+    /// more slowly.
+    pub fn set_from_ship(&mut self, engine: &Engine, hull: &Hull) {
+        let agility = hull.agility();
+        self.traction = engine.traction * agility;
+        self.strafe_traction = engine.strafe_traction * agility;
+        self.turn_speed = engine.turn_speed * agility;
+        self.angular_acceleration = engine.angular_acceleration * agility;
+        self.angular_damping = hull.angular_damping;
+        self.max_speed = hull.max_speed * engine.speed_multiplier;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inherit_forward_component() {
+        let ship = Controller {
+            velocity: Vector3::new(Float::from(3.0), Float::from(4.0), Float::from(0.0)),
+            ..Controller::default()
+        };
+        let projectile = Projectile {
+            inheritance: Inheritance::Forward,
+            ..Projectile::default()
+        };
+        let shot = ship.set_from_projectile(&projectile, &UnitQuaternion::identity());
+        assert_eq!(
+            shot.velocity,
+            Vector3::new(Float::from(0.0), Float::from(4.0), Float::from(0.0))
+        );
+    }
+
+    #[test]
+    fn launch_speed() {
+        let ship = Controller {
+            velocity: Vector3::new(Float::from(3.0), Float::from(4.0), Float::from(0.0)),
+            ..Controller::default()
+        };
+        let rotation = UnitQuaternion::identity();
+
+        // By default shots are pushed forward as fast as the ship is moving
+        let projectile = Projectile {
+            inheritance: Inheritance::None,
+            ..Projectile::default()
+        };
+        let shot = ship.clone().set_from_projectile(&projectile, &rotation);
+        assert_eq!(shot.start_speed, Float::from(5.0));
+
+        let projectile = Projectile {
+            launch: Launch::InitialSpeed,
+            ..projectile
+        };
+        let shot = ship.set_from_projectile(&projectile, &rotation);
+        assert_eq!(shot.start_speed, projectile.initial_speed);
+    }
+
+    #[test]
+    fn flight_assist_cancels_drift() {
+        let mut ship = Controller {
+            velocity: Vector3::new(Float::from(3.0), Float::from(4.0), Float::from(0.0)),
+            flight_model: FlightModel::Assisted {
+                strength: Float::from(1.0),
+            },
+            ..Controller::default()
+        };
+        ship.apply_flight_model(&Vector3::y(), Float::from(1.0));
+        assert_eq!(
+            ship.velocity,
+            Vector3::new(Float::from(0.0), Float::from(4.0), Float::from(0.0))
+        );
+
+        // Switching assist off leaves the drift alone
+        ship.velocity.x = Float::from(3.0);
+        ship.flight_assist = false;
+        ship.apply_flight_model(&Vector3::y(), Float::from(1.0));
+        assert_eq!(ship.velocity.x, Float::from(3.0));
+    }
+
+    #[test]
+    fn drag_never_reverses() {
+        let mut ship = Controller {
+            velocity: Vector3::new(Float::from(0.0), Float::from(10.0), Float::from(0.0)),
+            flight_model: FlightModel::Arcade(Drag::Quadratic(Float::from(1.0))),
+            ..Controller::default()
+        };
+        ship.apply_flight_model(&Vector3::y(), Float::from(1.0));
+        assert_eq!(ship.velocity, Vector3::zeros());
+    }
+
+    #[test]
+    fn dropped_mines_come_to_rest() {
+        let ship = Controller {
+            velocity: Vector3::new(Float::from(0.0), Float::from(50.0), Float::from(0.0)),
+            ..Controller::default()
+        };
+        let projectile = Projectile {
+            traction: Float::from(20.0),
+            ..Projectile::default()
+        };
+        let mut mine = ship.set_from_mine(&projectile, &UnitQuaternion::identity());
+
+        // The mine drifts for a while, then stops for good
+        let step = Float::from(1.0 / 60.0);
+        mine.apply_flight_model(&Vector3::y(), step);
+        assert!(mine.velocity.y > Float::from(0.0));
+        for _ in 0..(60 * 3) {
+            mine.apply_flight_model(&Vector3::y(), step);
+        }
+        assert_eq!(mine.velocity, Vector3::zeros());
+    }
+
+    #[test]
+    fn turning_builds_up_with_inertia() {
+        let mut instant = Controller {
+            rotation_control: Float::from(1.0),
+            turn_speed: Float::from(4.0),
+            ..Controller::default()
+        };
+        assert_eq!(instant.turn(Float::from(0.5)), Float::from(2.0));
+
+        let mut heavy = Controller {
+            angular_acceleration: Float::from(2.0),
+            ..instant.clone()
+        };
+        heavy.angular_velocity = Float::from(0.0);
+        assert_eq!(heavy.turn(Float::from(0.5)), Float::from(0.5));
+        assert_eq!(heavy.angular_velocity, Float::from(1.0));
+
+        // Letting go keeps the ship spinning until damping slows it
+        heavy.rotation_control = Float::from(0.0);
+        heavy.angular_damping = Float::from(1.0);
+        heavy.turn(Float::from(0.5));
+        assert_eq!(heavy.angular_velocity, Float::from(0.5));
+    }
+
+    #[test]
+    fn heavier_hulls_are_sluggish() {
+        let engine = Engine::default();
+        let mut light = Controller::default();
+        light.set_from_ship(&engine, &Hull::default());
+        let mut heavy = Controller::default();
+        heavy.set_from_ship(
+            &engine,
+            &Hull {
+                weight: 200,
+                ..Hull::default()
+            },
+        );
+        assert_eq!(heavy.traction, light.traction / Float::from(2.0));
+        assert_eq!(heavy.turn_speed, light.turn_speed / Float::from(2.0));
+        assert_eq!(heavy.max_speed, light.max_speed);
+    }
 }
@@ -6,22 +6,36 @@ use amethyst::{
     error::Error,
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
+/// Drives a ship. Together with the `Hull` it decides how the ship's `Controller` handles.
 #[derive(Clone, Deserialize, Serialize, PrefabData)]
 #[serde(default)]
 #[prefab(Component)]
 pub struct Engine {
+    /// Acceleration at full thrust for a hull of reference weight
     pub traction: Float,
+    /// Acceleration of the lateral thrusters for a hull of reference weight
+    pub strafe_traction: Float,
+    /// Turn speed for a hull of reference weight
     pub turn_speed: Float,
+    /// How quickly turning speeds up for a hull of reference weight. `0` turns instantly.
+    pub angular_acceleration: Float,
+    /// Scales the hull's top speed
     pub speed_multiplier: Float,
+    /// Afterburner that can be fired for extra speed. `None` means the engine can't boost.
+    pub boost: Option<Boost>,
 }
 
 impl Default for Engine {
     fn default() -> Self {
         Self {
-            traction: Float::from(0.0),
-            turn_speed: Float::from(0.0),
-            speed_multiplier: Float::from(0.0),
+            traction: Float::from(1000.0),
+            strafe_traction: Float::from(600.0),
+            turn_speed: Float::from(6.0),
+            angular_acceleration: Float::from(0.0),
+            speed_multiplier: Float::from(1.0),
+            boost: None,
         }
     }
 }
@@ -29,3 +43,100 @@ impl Default for Engine {
 impl Component for Engine {
     type Storage = DenseVecStorage<Self>;
 }
+
+/// Raises top speed and acceleration while boosting, draining an energy reserve that recharges
+/// once the boost has been let go for a while
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Boost {
+    /// Top speed multiplier while boosting
+    pub speed_multiplier: Float,
+    /// Acceleration multiplier while boosting
+    pub traction_multiplier: Float,
+    /// Energy in a full reserve
+    pub capacity: Float,
+    /// Energy used every second of boosting
+    pub drain: Float,
+    /// Energy regained every second once recharging starts
+    pub recharge: Float,
+    /// How long after boosting the reserve starts to recharge
+    pub recharge_delay: Duration,
+    /// Energy used up so far
+    #[serde(skip)]
+    spent: Float,
+    /// Absolute time the boost was last used
+    #[serde(skip)]
+    last_used: Option<Duration>,
+}
+
+impl Default for Boost {
+    fn default() -> Self {
+        Self {
+            speed_multiplier: Float::from(1.5),
+            traction_multiplier: Float::from(2.0),
+            capacity: Float::from(100.0),
+            drain: Float::from(50.0),
+            recharge: Float::from(25.0),
+            recharge_delay: Duration::from_secs(1),
+            spent: Float::from(0.0),
+            last_used: None,
+        }
+    }
+}
+
+impl Boost {
+    /// Energy left in the reserve
+    pub fn energy(&self) -> Float {
+        self.capacity - self.spent
+    }
+
+    /// Drains energy if `boosting` and there is any left, otherwise recharges once the delay has
+    /// passed. Holding boost with an empty reserve keeps it from recharging. Returns whether the
+    /// boost is active this frame.
+    pub fn update(&mut self, boosting: bool, now: Duration, delta_seconds: Float) -> bool {
+        if boosting {
+            self.last_used = Some(now);
+            if self.energy() > Float::from(0.0) {
+                self.spent += self.drain * delta_seconds;
+                if self.spent > self.capacity {
+                    self.spent = self.capacity;
+                }
+                return true;
+            }
+            return false;
+        }
+
+        let recharging = self
+            .last_used
+            .map_or(true, |last_used| now >= last_used + self.recharge_delay);
+        if recharging {
+            self.spent -= self.recharge * delta_seconds;
+            if self.spent < Float::from(0.0) {
+                self.spent = Float::from(0.0);
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boost_drains_then_recharges_after_delay() {
+        let mut boost = Boost::default();
+        let second = Float::from(1.0);
+        assert!(boost.update(true, Duration::from_secs(0), second));
+        assert!(boost.update(true, Duration::from_secs(1), second));
+        // The reserve is empty, and holding boost keeps putting off the recharge
+        assert!(!boost.update(true, Duration::from_secs(2), second));
+        assert!(!boost.update(true, Duration::from_secs(4), second));
+        assert_eq!(boost.energy(), Float::from(0.0));
+        // Still too soon after letting go
+        assert!(!boost.update(false, Duration::from_millis(4500), second));
+        assert_eq!(boost.energy(), Float::from(0.0));
+        boost.update(false, Duration::from_secs(5), second);
+        assert_eq!(boost.energy(), boost.recharge);
+    }
+}
@@ -0,0 +1,19 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    error::Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// The side an entity fights on. Entities in the same faction are allies.
+#[derive(Clone, Deserialize, Serialize, PrefabData, Default, Debug, PartialEq)]
+#[serde(default)]
+#[prefab(Component)]
+pub struct Faction {
+    pub name: String,
+}
+
+impl Component for Faction {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,47 @@
+use amethyst::{
+    assets::PrefabData,
+    core::Float,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    error::Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// A named spot on a ship that a weapon can be mounted on
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct Hardpoint {
+    pub name: String,
+    /// Position relative to the ship's center, along its local X and Y axes
+    pub offset: (Float, Float),
+    /// Angle in radians the mounted weapon faces, relative to the ship's heading
+    pub angle: Float,
+}
+
+impl Default for Hardpoint {
+    fn default() -> Self {
+        Self {
+            name: String::from("Hardpoint"),
+            offset: (Float::from(0.0), Float::from(0.0)),
+            angle: Float::from(0.0),
+        }
+    }
+}
+
+/// Every hardpoint on a ship
+#[derive(Clone, Deserialize, Serialize, PrefabData, Default, Debug)]
+#[serde(default)]
+#[prefab(Component)]
+pub struct Hardpoints {
+    points: Vec<Hardpoint>,
+}
+
+impl Component for Hardpoints {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Hardpoints {
+    pub fn get(&self, name: &str) -> Option<&Hardpoint> {
+        self.points.iter().find(|point| point.name == name)
+    }
+}
@@ -0,0 +1,63 @@
+use amethyst::{
+    assets::PrefabData,
+    core::Float,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    error::Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// Hit points of anything that can be damaged. An entity is destroyed once its health runs out.
+#[derive(Clone, Deserialize, Serialize, PrefabData, Debug, PartialEq)]
+#[serde(default)]
+#[prefab(Component)]
+pub struct Health {
+    /// Hit points when undamaged
+    pub max: Float,
+    /// Radius used to check whether a projectile hits this entity
+    pub radius: Float,
+    /// Damage taken so far
+    #[serde(skip)]
+    damage_taken: Float,
+    /// Whoever dealt the most recent damage, so they can be credited with the kill
+    #[serde(skip)]
+    last_attacker: Option<Entity>,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            max: Float::from(100.0),
+            radius: Float::from(32.0),
+            damage_taken: Float::from(0.0),
+            last_attacker: None,
+        }
+    }
+}
+
+impl Component for Health {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Health {
+    /// Remaining hit points
+    pub fn current(&self) -> Float {
+        self.max - self.damage_taken
+    }
+
+    /// Removes `amount` hit points, remembering `attacker` if there was one
+    pub fn damage(&mut self, amount: Float, attacker: Option<Entity>) {
+        self.damage_taken += amount;
+        if attacker.is_some() {
+            self.last_attacker = attacker;
+        }
+    }
+
+    pub fn last_attacker(&self) -> Option<Entity> {
+        self.last_attacker
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current() <= Float::from(0.0)
+    }
+}
@@ -7,16 +7,23 @@ use amethyst::{
 };
 use serde::{Deserialize, Serialize};
 
+/// Weight of a hull that gets exactly the acceleration and turn speed its engine is rated for
+const REFERENCE_WEIGHT: f32 = 100.0;
+
 /// Specifies core traits about a ship
 #[derive(Clone, Deserialize, Serialize, PrefabData)]
 #[serde(default)]
 #[prefab(Component)]
 pub struct Hull {
-    // Will be used later when we have engines
+    /// How hard the ship is to move. Heavier ships turn and accelerate more slowly, and are shoved
+    /// less by weapon kickback.
     pub weight: usize,
     /// Used for calculating inventory space
     pub inventory_space: usize,
+    /// Top speed before the engine's `speed_multiplier` is applied
     pub max_speed: Float,
+    /// Fraction of its spin the ship loses every second
+    pub angular_damping: Float,
 }
 
 impl Default for Hull {
@@ -24,11 +31,25 @@ impl Default for Hull {
         Self {
             weight: 100,
             inventory_space: 100,
-            max_speed: Float::from(6.0),
+            max_speed: Float::from(500.0),
+            angular_damping: Float::from(5.0),
         }
     }
 }
 
+impl Hull {
+    /// How much of its engine's acceleration and turn speed the ship gets. Hulls heavier than the
+    /// reference weight get less, lighter ones get more.
+    pub fn agility(&self) -> Float {
+        Float::from(REFERENCE_WEIGHT / self.weight.max(1) as f32)
+    }
+
+    /// Change in speed caused by `impulse`, which shrinks as the ship gets heavier
+    pub fn velocity_change(&self, impulse: Float) -> Float {
+        impulse / Float::from(self.weight.max(1) as f32)
+    }
+}
+
 impl Component for Hull {
     type Storage = DenseVecStorage<Self>;
 }
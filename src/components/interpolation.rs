@@ -0,0 +1,16 @@
+use amethyst::{
+    core::Transform,
+    ecs::{Component, DenseVecStorage},
+};
+
+/// Where an entity was after the last two simulation ticks, so it can be drawn smoothly between
+/// them when frames don't line up with ticks
+#[derive(Clone, Debug)]
+pub struct Interpolation {
+    pub previous: Transform,
+    pub current: Transform,
+}
+
+impl Component for Interpolation {
+    type Storage = DenseVecStorage<Self>;
+}
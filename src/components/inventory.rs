@@ -5,3 +5,38 @@ use amethyst::{
     error::Error,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Items carried by a ship, counted by name
+#[derive(Clone, Deserialize, Serialize, PrefabData, Default, Debug, PartialEq)]
+#[serde(default)]
+#[prefab(Component)]
+pub struct Inventory {
+    items: HashMap<String, usize>,
+}
+
+impl Component for Inventory {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Inventory {
+    pub fn count(&self, item: &str) -> usize {
+        self.items.get(item).cloned().unwrap_or(0)
+    }
+
+    pub fn add(&mut self, item: &str, amount: usize) {
+        *self.items.entry(item.to_string()).or_insert(0) += amount;
+    }
+
+    /// Removes up to `amount` of an item and returns how many were actually taken
+    pub fn take(&mut self, item: &str, amount: usize) -> usize {
+        match self.items.get_mut(item) {
+            Some(count) => {
+                let taken = amount.min(*count);
+                *count -= taken;
+                taken
+            }
+            None => 0,
+        }
+    }
+}
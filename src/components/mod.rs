@@ -1,11 +1,22 @@
 mod controller;
 mod engine;
+mod faction;
+mod hardpoint;
+mod health;
 mod hull;
+mod interpolation;
 mod inventory;
 mod player;
 mod position;
 pub mod weapon;
 
-pub use controller::Controller;
+pub use controller::{Controller, Drag, FlightModel};
+pub use engine::{Boost, Engine};
+pub use faction::Faction;
+pub use hardpoint::{Hardpoint, Hardpoints};
+pub use health::Health;
+pub use hull::Hull;
+pub use interpolation::Interpolation;
+pub use inventory::Inventory;
 pub use player::Player;
 pub use position::Position;
@@ -1,10 +1,13 @@
+use crate::components::{Faction, Inventory};
 use amethyst::{
     assets::PrefabData,
-    core::{math::Vector3, Float},
+    core::{math::Vector3, timing::duration_to_secs, Float},
     derive::PrefabData,
     ecs::{Component, DenseVecStorage, Entity, WriteStorage},
     error::Error,
+    renderer::SpriteRender,
 };
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -17,10 +20,26 @@ pub struct Weapon {
     speed: Float,
     /// Time taken between consecutive shots
     pub recoil: Duration,
+    /// Impulse that pushes the firing ship backwards with every shot. How far the ship is pushed
+    /// depends on its `Hull::weight`.
+    pub kickback: Float,
     /// Absolute time that weapon was last fired
     pub last_fired: Duration,
+    /// How the weapon delivers its damage
+    pub kind: WeaponKind,
     /// Component that will be attached to this weapon
     pub projectile: Projectile,
+    /// How many projectiles are fired per shot and where they go
+    pub pattern: FirePattern,
+    /// Magazine and reserve ammunition. `None` means the weapon never runs out.
+    pub ammo: Option<Ammo>,
+    /// Heat built up by firing. `None` means the weapon never overheats.
+    pub heat: Option<Heat>,
+    /// Makes the weapon charge while fire is held and shoot when it is released
+    pub charge: Option<Charge>,
+    /// Name of the ship's `Hardpoint` this weapon is mounted on. Unmounted weapons, or weapons on a
+    /// hardpoint the ship doesn't have, fire from the ship's center.
+    pub hardpoint: Option<String>,
 }
 
 impl Component for Weapon {
@@ -33,13 +52,45 @@ impl Default for Weapon {
             name: String::from("Weapon"),
             speed: Float::from(10.),
             recoil: Duration::from_millis(50),
+            kickback: Float::from(0.0),
             last_fired: Duration::from_secs(0),
+            kind: WeaponKind::Projectile,
             projectile: Projectile::default(),
+            pattern: FirePattern::default(),
+            ammo: None,
+            heat: None,
+            charge: None,
+            hardpoint: None,
         }
     }
 }
 
 impl Weapon {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the weapon has ammo loaded and isn't overheated at `now`. Recoil is checked separately.
+    pub fn is_ready(&self, now: Duration) -> bool {
+        let loaded = self.ammo.as_ref().map_or(true, Ammo::is_ready);
+        let cool = self
+            .heat
+            .as_ref()
+            .map_or(true, |heat| !heat.is_overheated(now));
+        loaded && cool
+    }
+
+    /// Resets the recoil timer and charges the shot against the weapon's ammo and heat
+    pub fn record_shot(&mut self, now: Duration) {
+        self.last_fired = now;
+        if let Some(ammo) = self.ammo.as_mut() {
+            ammo.consume();
+        }
+        if let Some(heat) = self.heat.as_mut() {
+            heat.add_shot(now);
+        }
+    }
+
     /// Useful for testing equality without needing to build an entire `Weapon`.
     fn set_name(mut self, name: String) -> Self {
         self.name = name;
@@ -47,16 +98,447 @@ impl Weapon {
     }
 }
 
+/// How a weapon delivers its damage
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum WeaponKind {
+    /// Fires simulated `Projectile`s
+    Projectile,
+    /// Instantly damages the first thing in front of the ship, once per shot
+    Hitscan { range: Float, damage: Float },
+    /// Damages the first thing in front of the ship every frame the trigger is held. Ammo and heat
+    /// are charged once every `recoil`.
+    Beam {
+        range: Float,
+        damage_per_second: Float,
+    },
+    /// Drops the `Projectile` behind the ship, where it sits with no thrust of its own. Use
+    /// `Projectile::arm_time` and a proximity `Explosion` to make it a mine.
+    Mine { drop_distance: Float },
+}
+
+/// Where a weapon gets rounds from when it reloads
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Reserve {
+    /// Magazines refill for free
+    Unlimited,
+    /// Spare rounds carried by the weapon itself
+    Internal(usize),
+    /// Spare rounds are taken from the ship's `Inventory` under the ammo type's name
+    Inventory,
+}
+
+/// Limits a weapon to a magazine of rounds that has to be reloaded from a reserve
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Ammo {
+    /// Name of the ammunition, used to find reserve rounds in the ship's `Inventory`
+    pub ammo_type: String,
+    /// Rounds in a full magazine
+    pub magazine_size: usize,
+    /// Time taken to refill an empty magazine
+    pub reload_time: Duration,
+    pub reserve: Reserve,
+    /// Rounds fired since the magazine was last full
+    #[serde(skip)]
+    spent: usize,
+    /// Absolute time the current reload started
+    #[serde(skip)]
+    reload_started: Option<Duration>,
+}
+
+impl Default for Ammo {
+    fn default() -> Self {
+        Self {
+            ammo_type: String::from("bullet"),
+            magazine_size: 30,
+            reload_time: Duration::from_secs(2),
+            reserve: Reserve::Unlimited,
+            spent: 0,
+            reload_started: None,
+        }
+    }
+}
+
+impl Ammo {
+    /// Rounds left in the magazine
+    pub fn loaded(&self) -> usize {
+        self.magazine_size.saturating_sub(self.spent)
+    }
+
+    pub fn is_reloading(&self) -> bool {
+        self.reload_started.is_some()
+    }
+
+    /// Whether a round can be fired right now
+    pub fn is_ready(&self) -> bool {
+        self.loaded() > 0 && !self.is_reloading()
+    }
+
+    /// Uses up a round from the magazine
+    pub fn consume(&mut self) {
+        self.spent = (self.spent + 1).min(self.magazine_size);
+    }
+
+    /// Starts reloading an empty magazine and refills it once `reload_time` has passed
+    pub fn update(&mut self, now: Duration, inventory: Option<&mut Inventory>) {
+        match self.reload_started {
+            Some(started) => {
+                if now >= started + self.reload_time {
+                    let rounds = self.take_reserve(self.spent, inventory);
+                    self.spent -= rounds;
+                    self.reload_started = None;
+                }
+            }
+            None => {
+                if self.loaded() == 0 && self.has_reserve(inventory.as_ref().map(|i| &**i)) {
+                    self.reload_started = Some(now);
+                }
+            }
+        }
+    }
+
+    fn has_reserve(&self, inventory: Option<&Inventory>) -> bool {
+        match self.reserve {
+            Reserve::Unlimited => true,
+            Reserve::Internal(rounds) => rounds > 0,
+            Reserve::Inventory => {
+                inventory.map_or(false, |inventory| inventory.count(&self.ammo_type) > 0)
+            }
+        }
+    }
+
+    /// Removes up to `wanted` rounds from the reserve and returns how many were taken
+    fn take_reserve(&mut self, wanted: usize, inventory: Option<&mut Inventory>) -> usize {
+        match &mut self.reserve {
+            Reserve::Unlimited => wanted,
+            Reserve::Internal(rounds) => {
+                let taken = wanted.min(*rounds);
+                *rounds -= taken;
+                taken
+            }
+            Reserve::Inventory => {
+                inventory.map_or(0, |inventory| inventory.take(&self.ammo_type, wanted))
+            }
+        }
+    }
+}
+
+/// Heat that builds up with each shot and locks the weapon out when it gets too hot
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Heat {
+    /// Heat added by each shot
+    pub per_shot: Float,
+    /// Heat removed every second
+    pub dissipation: Float,
+    /// Heat at which the weapon overheats
+    pub capacity: Float,
+    /// How long the weapon can't fire after overheating
+    pub overheat_penalty: Duration,
+    #[serde(skip)]
+    current: Float,
+    /// Absolute time the overheat lockout ends
+    #[serde(skip)]
+    locked_until: Option<Duration>,
+}
+
+impl Default for Heat {
+    fn default() -> Self {
+        Self {
+            per_shot: Float::from(10.0),
+            dissipation: Float::from(25.0),
+            capacity: Float::from(100.0),
+            overheat_penalty: Duration::from_secs(2),
+            current: Float::from(0.0),
+            locked_until: None,
+        }
+    }
+}
+
+impl Heat {
+    pub fn current(&self) -> Float {
+        self.current
+    }
+
+    pub fn is_overheated(&self, now: Duration) -> bool {
+        self.locked_until.map_or(false, |until| now < until)
+    }
+
+    /// Dissipates heat over `delta_seconds` and ends the lockout once it has run its course
+    pub fn cool(&mut self, now: Duration, delta_seconds: Float) {
+        self.current -= self.dissipation * delta_seconds;
+        if self.current < Float::from(0.0) {
+            self.current = Float::from(0.0);
+        }
+        if !self.is_overheated(now) {
+            self.locked_until = None;
+        }
+    }
+
+    /// Adds the heat of a shot, locking the weapon out if it reaches capacity
+    pub fn add_shot(&mut self, now: Duration) {
+        self.current += self.per_shot;
+        if self.current >= self.capacity {
+            self.locked_until = Some(now + self.overheat_penalty);
+        }
+    }
+}
+
+/// Lets a weapon build up charge while fire is held, and fire a stronger shot when it is released
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Charge {
+    /// Time fire has to be held to reach full charge
+    pub time: Duration,
+    /// Charge level from 0 to 1 needed for a release to fire anything
+    pub minimum: Float,
+    /// Fire as soon as the weapon is fully charged instead of waiting for a release
+    pub auto_release: bool,
+    /// Damage multiplier at full charge
+    pub damage_scale: Float,
+    /// Projectile speed multiplier at full charge
+    pub speed_scale: Float,
+    /// Projectile size multiplier at full charge
+    pub size_scale: Float,
+    /// Current charge level from 0 to 1
+    #[serde(skip)]
+    level: Float,
+    /// Whether the charge has been let go and is waiting for the weapon to be ready to fire it
+    #[serde(skip)]
+    released: bool,
+}
+
+impl Default for Charge {
+    fn default() -> Self {
+        Self {
+            time: Duration::from_secs(1),
+            minimum: Float::from(0.0),
+            auto_release: false,
+            damage_scale: Float::from(3.0),
+            speed_scale: Float::from(1.5),
+            size_scale: Float::from(2.0),
+            level: Float::from(0.0),
+            released: false,
+        }
+    }
+}
+
+impl Charge {
+    pub fn level(&self) -> Float {
+        self.level
+    }
+
+    /// Builds charge while the trigger is held. Returns the charge level to fire with once the
+    /// trigger is released, or once the weapon auto releases at full charge. The charge stays
+    /// released until `take` is called, so a weapon that can't fire yet keeps it for later.
+    pub fn update(&mut self, trigger: TriggerState, delta_seconds: Float) -> Option<Float> {
+        if !self.released {
+            match trigger {
+                TriggerState::Pressed | TriggerState::Held => {
+                    let charge_time = duration_to_secs(self.time);
+                    self.level = if charge_time > 0.0 {
+                        self.level + delta_seconds / Float::from(charge_time)
+                    } else {
+                        Float::from(1.0)
+                    };
+                    if self.level >= Float::from(1.0) {
+                        self.level = Float::from(1.0);
+                        if self.auto_release {
+                            self.release();
+                        }
+                    }
+                }
+                TriggerState::Released => self.release(),
+                TriggerState::Idle => {}
+            }
+        }
+
+        if self.released {
+            Some(self.level)
+        } else {
+            None
+        }
+    }
+
+    /// Lets go of the charge, or empties it if it wasn't enough to fire
+    fn release(&mut self) {
+        if self.level >= self.minimum {
+            self.released = true;
+        } else {
+            self.level = Float::from(0.0);
+        }
+    }
+
+    /// Empties the charge once its shot has been fired
+    pub fn take(&mut self) {
+        self.level = Float::from(0.0);
+        self.released = false;
+    }
+
+    /// Scales `value` from itself at no charge up to `value * full_scale` at full charge
+    pub fn scale(value: Float, full_scale: Float, level: Float) -> Float {
+        value * (Float::from(1.0) + (full_scale - Float::from(1.0)) * level)
+    }
+
+    /// Makes a projectile as strong as a shot charged to `level`
+    pub fn apply(&self, projectile: &mut Projectile, level: Float) {
+        projectile.damage = Self::scale(projectile.damage, self.damage_scale, level);
+        projectile.initial_speed = Self::scale(projectile.initial_speed, self.speed_scale, level);
+        projectile.max_speed = Self::scale(projectile.max_speed, self.speed_scale, level);
+        projectile.radius = Self::scale(projectile.radius, self.size_scale, level);
+    }
+}
+
+/// How the projectiles of a single shot are spread out
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Spread {
+    /// Projectiles are evenly spaced across the spread angle
+    Fan,
+    /// Each projectile gets a random angle inside the spread angle
+    Random,
+}
+
+/// Describes how many projectiles a weapon fires per shot and from where
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FirePattern {
+    /// Projectiles fired from each barrel per shot
+    pub count: usize,
+    /// Total angle in radians that projectiles are spread across
+    pub spread: Float,
+    pub spread_kind: Spread,
+    /// Sideways offset of each barrel from the center of the ship
+    pub barrels: Vec<Float>,
+    /// Fire one barrel per shot in turn instead of every barrel at once
+    pub alternate: bool,
+    /// Barrel that fires next when alternating
+    #[serde(skip)]
+    pub next_barrel: usize,
+}
+
+impl Default for FirePattern {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            spread: Float::from(0.0),
+            spread_kind: Spread::Fan,
+            barrels: vec![Float::from(0.0)],
+            alternate: false,
+            next_barrel: 0,
+        }
+    }
+}
+
+impl FirePattern {
+    /// Angles in radians, relative to the ship's heading, of every projectile in a single shot
+    pub fn angles<R: Rng>(&self, rng: &mut R) -> Vec<Float> {
+        let half_spread = self.spread / Float::from(2.0);
+        match self.spread_kind {
+            Spread::Fan => {
+                if self.count <= 1 {
+                    return vec![Float::from(0.0); self.count];
+                }
+                let step = self.spread / Float::from((self.count - 1) as f32);
+                (0..self.count)
+                    .map(|i| Float::from(i as f32) * step - half_spread)
+                    .collect()
+            }
+            Spread::Random => (0..self.count)
+                .map(|_| Float::from(rng.gen::<f32>()) * self.spread - half_spread)
+                .collect(),
+        }
+    }
+
+    /// Offsets of the barrels that fire this shot. Advances to the next barrel when alternating.
+    pub fn next_barrels(&mut self) -> Vec<Float> {
+        if self.barrels.is_empty() {
+            return vec![Float::from(0.0)];
+        }
+        if self.alternate {
+            let barrel = self.barrels[self.next_barrel % self.barrels.len()];
+            self.next_barrel = (self.next_barrel + 1) % self.barrels.len();
+            vec![barrel]
+        } else {
+            self.barrels.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Projectile {
     pub max_speed: Float,
     pub traction: Float,
     /// Initial speed added to the intial velocity. A higher value will act more like a bullet, a lower
     /// will act more like a missle.
     pub initial_speed: Float,
+    /// How long the projectile lives before it is removed. `None` means it never expires with age.
+    pub lifetime: Option<Duration>,
+    /// How far the projectile can travel before it is removed. `None` means there is no distance limit.
+    pub max_distance: Option<Float>,
+    /// Health removed from whatever the projectile hits
+    pub damage: Float,
+    /// Radius used for hit detection
+    pub radius: Float,
+    /// Makes the projectile steer towards targets like a missile
+    pub homing: Option<Homing>,
+    /// What the projectile looks like
+    pub sprite: ProjectileSprite,
+    /// How much of the firing ship's velocity the projectile keeps
+    pub inheritance: Inheritance,
+    /// How fast the projectile is pushed forward when it is fired
+    pub launch: Launch,
+    /// Makes the projectile explode and damage everything around it
+    pub explosion: Option<Explosion>,
+    /// Time after firing before the projectile can hit anything or be set off
+    pub arm_time: Duration,
+    /// Who the projectile is allowed to hurt
+    pub friendly_fire: FriendlyFire,
+    /// Entity that fired this projectile
+    #[serde(skip)]
+    pub owner: Option<Entity>,
+    /// Faction of the entity that fired this projectile
+    #[serde(skip)]
+    pub faction: Option<String>,
+    /// Time since the projectile was fired
+    #[serde(skip)]
+    pub age: Duration,
+    /// Distance covered since the projectile was fired
+    #[serde(skip)]
+    pub distance_traveled: Float,
+    /// Whether the projectile has flown clear of the entity that fired it. Until then it can't hit
+    /// its owner, whatever its `friendly_fire` rule.
+    #[serde(skip)]
+    pub left_owner: bool,
 }
 
-impl Projectile {}
+impl Projectile {
+    /// Whether this projectile is allowed to hurt `target`
+    pub fn can_hit(&self, target: Entity, target_faction: Option<&Faction>) -> bool {
+        if !self.left_owner && Some(target) == self.owner {
+            return false;
+        }
+        self.friendly_fire.allows(
+            self.owner,
+            self.faction.as_ref().map(String::as_str),
+            target,
+            target_faction,
+        )
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.age >= self.arm_time
+    }
+
+    /// Whether the projectile has outlived its lifetime or travelled past its maximum distance
+    pub fn is_expired(&self) -> bool {
+        let too_old = self.lifetime.map_or(false, |lifetime| self.age >= lifetime);
+        let too_far = self
+            .max_distance
+            .map_or(false, |max_distance| self.distance_traveled >= max_distance);
+        too_old || too_far
+    }
+}
 
 impl Default for Projectile {
     fn default() -> Self {
@@ -64,6 +546,22 @@ impl Default for Projectile {
             max_speed: Float::from(2000.0),
             traction: Float::from(1000.0),
             initial_speed: Float::from(500.0),
+            lifetime: Some(Duration::from_secs(3)),
+            max_distance: None,
+            damage: Float::from(10.0),
+            radius: Float::from(8.0),
+            homing: None,
+            sprite: ProjectileSprite::default(),
+            inheritance: Inheritance::Full,
+            launch: Launch::ShipSpeed,
+            explosion: None,
+            arm_time: Duration::from_secs(0),
+            friendly_fire: FriendlyFire::IgnoreAllies,
+            owner: None,
+            faction: None,
+            age: Duration::from_secs(0),
+            distance_traveled: Float::from(0.0),
+            left_owner: false,
         }
     }
 }
@@ -72,6 +570,191 @@ impl Component for Projectile {
     type Storage = DenseVecStorage<Self>;
 }
 
+/// Who a projectile or its explosion is allowed to hurt
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum FriendlyFire {
+    /// Hits everything except the entity that fired it
+    IgnoreOwner,
+    /// Hits everything except the entity that fired it and its faction
+    IgnoreAllies,
+    /// Hits everything, including the entity that fired it. Projectiles only start hitting their
+    /// owner once they have flown clear of it.
+    All,
+}
+
+impl FriendlyFire {
+    /// Whether a shot fired by `owner` from `faction` may hurt `target`
+    pub fn allows(
+        self,
+        owner: Option<Entity>,
+        faction: Option<&str>,
+        target: Entity,
+        target_faction: Option<&Faction>,
+    ) -> bool {
+        let is_owner = Some(target) == owner;
+        let is_ally = match (faction, target_faction) {
+            (Some(faction), Some(target_faction)) => faction == target_faction.name,
+            _ => false,
+        };
+        match self {
+            FriendlyFire::IgnoreOwner => !is_owner,
+            FriendlyFire::IgnoreAllies => !is_owner && !is_ally,
+            FriendlyFire::All => true,
+        }
+    }
+}
+
+/// How explosion damage and knockback drop off toward the edge of the blast
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Falloff {
+    /// Full strength across the whole blast
+    None,
+    Linear,
+    Quadratic,
+}
+
+/// A blast that damages and pushes away everything within its radius
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Explosion {
+    pub radius: Float,
+    /// Damage at the center of the blast
+    pub damage: Float,
+    pub falloff: Falloff,
+    /// Speed added to anything caught in the blast, away from its center
+    pub knockback: Float,
+    /// Explode when the projectile hits something
+    pub on_hit: bool,
+    /// Explode when the projectile's lifetime or distance runs out
+    pub on_expire: bool,
+    /// Explode as soon as a target comes within this distance
+    pub proximity: Option<Float>,
+}
+
+impl Default for Explosion {
+    fn default() -> Self {
+        Self {
+            radius: Float::from(100.0),
+            damage: Float::from(30.0),
+            falloff: Falloff::Linear,
+            knockback: Float::from(200.0),
+            on_hit: true,
+            on_expire: false,
+            proximity: None,
+        }
+    }
+}
+
+impl Explosion {
+    /// Fraction of the blast's full strength felt at `distance` from its center
+    pub fn strength_at(&self, distance: Float) -> Float {
+        if distance >= self.radius {
+            return Float::from(0.0);
+        }
+        let remaining = Float::from(1.0) - distance / self.radius;
+        match self.falloff {
+            Falloff::None => Float::from(1.0),
+            Falloff::Linear => remaining,
+            Falloff::Quadratic => remaining * remaining,
+        }
+    }
+}
+
+/// How much of the firing ship's velocity a projectile keeps
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Inheritance {
+    /// Ignore the ship's motion entirely
+    None,
+    /// Keep the ship's whole velocity
+    Full,
+    /// Keep only the part of the ship's velocity along the direction of the shot
+    Forward,
+    /// Keep the ship's velocity multiplied by a factor
+    Scale(Float),
+}
+
+/// Speed a projectile is pushed forward with when it is fired, on top of the velocity it inherits
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Launch {
+    /// As fast as the firing ship is moving. `initial_speed` is only the slowest the projectile flies.
+    ShipSpeed,
+    /// Exactly `initial_speed`, however fast the ship is moving
+    InitialSpeed,
+}
+
+/// Identifies a sprite inside a sprite sheet
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum SpriteId {
+    Index(usize),
+    /// A name listed for the sheet in `config/sprite_sheets.ron`
+    Name(String),
+}
+
+/// The sprite drawn for a projectile. It is looked up once prefabs have loaded.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProjectileSprite {
+    /// Name of a sheet listed in `config/sprite_sheets.ron`
+    pub sheet: String,
+    pub sprite: SpriteId,
+    /// The sprite that was found for `sheet` and `sprite`
+    #[serde(skip)]
+    pub render: Option<SpriteRender>,
+}
+
+impl Default for ProjectileSprite {
+    fn default() -> Self {
+        Self {
+            sheet: String::from("projectiles"),
+            sprite: SpriteId::Index(0),
+            render: None,
+        }
+    }
+}
+
+/// What a homing projectile does when it needs a target
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Retarget {
+    /// Chase the first target acquired and fly straight once it is destroyed
+    Never,
+    /// Pick a new target once the current one is destroyed or leaves the acquisition cone
+    OnLoss,
+    /// Always chase the closest target in the acquisition cone
+    Nearest,
+}
+
+/// Steering settings for projectiles that chase a target
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Homing {
+    /// How fast the projectile can turn, in radians per second
+    pub turn_rate: Float,
+    /// Half-angle in radians of the cone in front of the projectile where targets are acquired
+    pub cone: Float,
+    /// Maximum distance a target can be acquired or chased from
+    pub range: Float,
+    pub retarget: Retarget,
+    /// Entity currently being chased
+    #[serde(skip)]
+    pub target: Option<Entity>,
+    /// Whether a target has ever been acquired
+    #[serde(skip)]
+    pub acquired: bool,
+}
+
+impl Default for Homing {
+    fn default() -> Self {
+        Self {
+            turn_rate: Float::from(3.0),
+            cone: Float::from(std::f32::consts::FRAC_PI_4),
+            range: Float::from(800.0),
+            retarget: Retarget::OnLoss,
+            target: None,
+            acquired: false,
+        }
+    }
+}
+
 /// A weapon manager references weapons that are currently in the inventory. It switches active weapon and also
 /// equips/unequips weapons.
 #[derive(Clone, Deserialize, Serialize, PrefabData, Default)]
@@ -80,14 +763,50 @@ impl Component for Projectile {
 pub struct WeaponManager {
     weapons: Vec<Weapon>,
     active_index: usize,
+    /// Groups of weapons that fire together from their own action
+    pub fire_groups: Vec<FireGroup>,
     pub wants_to_fire: bool,
+    /// How the fire trigger changed this frame
+    #[serde(skip)]
+    trigger: TriggerState,
 }
 
 impl WeaponManager {
+    /// Updates the trigger with whether fire is held down this frame
+    pub fn set_trigger(&mut self, down: bool) {
+        self.trigger = self.trigger.next(down);
+        self.wants_to_fire = down;
+    }
+
+    pub fn trigger(&self) -> TriggerState {
+        self.trigger
+    }
+
     pub fn weapons(&self) -> &Vec<Weapon> {
         &self.weapons
     }
 
+    pub fn weapons_mut(&mut self) -> &mut Vec<Weapon> {
+        &mut self.weapons
+    }
+
+    /// Cools and reloads every weapon, including the ones that aren't in use
+    pub fn update_weapons(
+        &mut self,
+        now: Duration,
+        delta_seconds: Float,
+        mut inventory: Option<&mut Inventory>,
+    ) {
+        for weapon in &mut self.weapons {
+            if let Some(heat) = weapon.heat.as_mut() {
+                heat.cool(now, delta_seconds);
+            }
+            if let Some(ammo) = weapon.ammo.as_mut() {
+                ammo.update(now, inventory.as_mut().map(|i| &mut **i));
+            }
+        }
+    }
+
     pub fn current_weapon(&self) -> Option<&Weapon> {
         if self.weapons.len() != 0 {
             Some(&self.weapons[self.active_index])
@@ -135,13 +854,43 @@ impl WeaponManager {
         }
     }
 
+    pub fn fire_groups_mut(&mut self) -> &mut Vec<FireGroup> {
+        &mut self.fire_groups
+    }
+
+    /// Index of every weapon that can fire this frame, along with the trigger that controls it.
+    /// Each weapon follows exactly one trigger: the current weapon follows the fire trigger, and
+    /// every other weapon follows the first fire group that lists it.
+    pub fn triggered_weapons(&self) -> Vec<(usize, TriggerState)> {
+        let mut triggered = vec![];
+        if !self.weapons.is_empty() {
+            triggered.push((self.active_index, self.trigger));
+        }
+
+        for group in &self.fire_groups {
+            for index in &group.weapons {
+                let seen = triggered.iter().any(|(seen, _)| seen == index);
+                if *index < self.weapons.len() && !seen {
+                    triggered.push((*index, group.trigger));
+                }
+            }
+        }
+        triggered
+    }
+
     fn index(&self) -> usize {
         self.active_index
     }
 
-    /// If the user wants to select their weapon from an array of weapons, they can set the index manually
-    pub fn set_index(&mut self, new_index: usize) {
-        self.active_index = new_index;
+    /// If the user wants to select their weapon from an array of weapons, they can set the index manually.
+    /// Indices outside of the equipped weapons are ignored and return `None`.
+    pub fn set_index(&mut self, new_index: usize) -> Option<&Weapon> {
+        if new_index < self.weapons.len() {
+            self.active_index = new_index;
+            self.weapons.get(new_index)
+        } else {
+            None
+        }
     }
 }
 
@@ -154,6 +903,73 @@ pub enum Direction {
     Backward,
 }
 
+/// Press, hold and release edges of the fire trigger
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriggerState {
+    Idle,
+    /// Went down this frame
+    Pressed,
+    /// Has been down since an earlier frame
+    Held,
+    /// Went up this frame
+    Released,
+}
+
+impl Default for TriggerState {
+    fn default() -> Self {
+        TriggerState::Idle
+    }
+}
+
+impl TriggerState {
+    pub fn is_down(self) -> bool {
+        self == TriggerState::Pressed || self == TriggerState::Held
+    }
+
+    /// The state following this one after a frame where the trigger is `down`
+    pub fn next(self, down: bool) -> Self {
+        match (self.is_down(), down) {
+            (false, true) => TriggerState::Pressed,
+            (true, true) => TriggerState::Held,
+            (true, false) => TriggerState::Released,
+            (false, false) => TriggerState::Idle,
+        }
+    }
+}
+
+/// Weapons that all fire together, such as a secondary group. Each group is fired by the
+/// `Action::FireGroup` with its index in the `WeaponManager`.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct FireGroup {
+    pub name: String,
+    /// Indices of the weapons in this group
+    pub weapons: Vec<usize>,
+    #[serde(skip)]
+    trigger: TriggerState,
+}
+
+impl Default for FireGroup {
+    fn default() -> Self {
+        Self {
+            name: String::from("Primary"),
+            weapons: vec![],
+            trigger: TriggerState::Idle,
+        }
+    }
+}
+
+impl FireGroup {
+    /// Updates the trigger with whether this group's action is held down this frame
+    pub fn set_trigger(&mut self, down: bool) {
+        self.trigger = self.trigger.next(down);
+    }
+
+    pub fn trigger(&self) -> TriggerState {
+        self.trigger
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,7 +981,9 @@ mod tests {
                 Weapon::default().set_name("2".to_string()),
             ],
             active_index: index,
+            fire_groups: vec![],
             wants_to_fire: false,
+            trigger: TriggerState::Idle,
         }
     }
 
@@ -208,4 +1026,232 @@ mod tests {
         );
         assert_eq!(wm.index(), 0);
     }
+
+    #[test]
+    fn set_index_in_range() {
+        let mut wm = create_manager(0);
+        assert_eq!(
+            wm.set_index(2).unwrap(),
+            &Weapon::default().set_name("2".to_string())
+        );
+        assert_eq!(wm.index(), 2);
+    }
+
+    #[test]
+    fn set_index_out_of_range() {
+        let mut wm = create_manager(1);
+        assert_eq!(wm.set_index(3), None);
+        assert_eq!(wm.index(), 1);
+    }
+
+    #[test]
+    fn fire_group_weapons() {
+        let mut wm = create_manager(1);
+        wm.set_trigger(true);
+        assert_eq!(wm.triggered_weapons(), vec![(1, TriggerState::Pressed)]);
+
+        wm.fire_groups = vec![
+            FireGroup {
+                name: "Secondary".to_string(),
+                weapons: vec![1, 2, 5],
+                ..FireGroup::default()
+            },
+            FireGroup {
+                name: "Rear".to_string(),
+                weapons: vec![2, 0],
+                ..FireGroup::default()
+            },
+        ];
+        wm.set_trigger(false);
+        wm.fire_groups[0].set_trigger(true);
+
+        // The current weapon only follows the fire trigger, even though it is also in a group. Every
+        // other weapon follows the first group it is in.
+        assert_eq!(
+            wm.triggered_weapons(),
+            vec![
+                (1, TriggerState::Released),
+                (2, TriggerState::Pressed),
+                (0, TriggerState::Idle)
+            ]
+        );
+    }
+
+    #[test]
+    fn trigger_edges() {
+        let mut wm = create_manager(0);
+        wm.set_trigger(true);
+        assert_eq!(wm.trigger(), TriggerState::Pressed);
+        wm.set_trigger(true);
+        assert_eq!(wm.trigger(), TriggerState::Held);
+        wm.set_trigger(false);
+        assert_eq!(wm.trigger(), TriggerState::Released);
+        wm.set_trigger(false);
+        assert_eq!(wm.trigger(), TriggerState::Idle);
+    }
+
+    #[test]
+    fn charge_release() {
+        let mut charge = Charge {
+            time: Duration::from_secs(1),
+            minimum: Float::from(0.25),
+            ..Charge::default()
+        };
+        assert_eq!(charge.update(TriggerState::Pressed, Float::from(0.5)), None);
+        assert_eq!(
+            charge.update(TriggerState::Released, Float::from(0.0)),
+            Some(Float::from(0.5))
+        );
+
+        // A released charge waits until it is fired, without building up any further
+        assert_eq!(
+            charge.update(TriggerState::Pressed, Float::from(0.5)),
+            Some(Float::from(0.5))
+        );
+        charge.take();
+        assert_eq!(charge.update(TriggerState::Idle, Float::from(0.0)), None);
+
+        // Too little charge doesn't fire
+        charge.update(TriggerState::Pressed, Float::from(0.125));
+        assert_eq!(
+            charge.update(TriggerState::Released, Float::from(0.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn fan_spread() {
+        let pattern = FirePattern {
+            count: 3,
+            spread: Float::from(1.0),
+            ..FirePattern::default()
+        };
+        assert_eq!(
+            pattern.angles(&mut rand::thread_rng()),
+            vec![Float::from(-0.5), Float::from(0.0), Float::from(0.5)]
+        );
+    }
+
+    #[test]
+    fn alternate_barrels() {
+        let mut pattern = FirePattern {
+            barrels: vec![Float::from(-10.0), Float::from(10.0)],
+            alternate: true,
+            ..FirePattern::default()
+        };
+        assert_eq!(pattern.next_barrels(), vec![Float::from(-10.0)]);
+        assert_eq!(pattern.next_barrels(), vec![Float::from(10.0)]);
+        assert_eq!(pattern.next_barrels(), vec![Float::from(-10.0)]);
+    }
+
+    #[test]
+    fn reload_from_internal_reserve() {
+        let mut ammo = Ammo {
+            magazine_size: 2,
+            reload_time: Duration::from_secs(1),
+            reserve: Reserve::Internal(1),
+            ..Ammo::default()
+        };
+        ammo.consume();
+        ammo.consume();
+        assert!(!ammo.is_ready());
+
+        ammo.update(Duration::from_secs(0), None);
+        assert!(ammo.is_reloading());
+
+        ammo.update(Duration::from_secs(1), None);
+        assert_eq!(ammo.loaded(), 1);
+        assert_eq!(ammo.reserve, Reserve::Internal(0));
+    }
+
+    #[test]
+    fn holstered_weapons_reload() {
+        let mut manager = create_manager(0);
+        let mut ammo = Ammo {
+            magazine_size: 1,
+            reload_time: Duration::from_secs(1),
+            ..Ammo::default()
+        };
+        ammo.consume();
+        manager.weapons_mut()[1].ammo = Some(ammo);
+
+        manager.update_weapons(Duration::from_secs(0), Float::from(0.0), None);
+        manager.update_weapons(Duration::from_secs(1), Float::from(0.0), None);
+        let ammo = manager.weapons()[1].ammo.as_ref().unwrap();
+        assert_eq!(ammo.loaded(), 1);
+    }
+
+    #[test]
+    fn overheat_lockout() {
+        let mut heat = Heat {
+            per_shot: Float::from(50.0),
+            dissipation: Float::from(10.0),
+            capacity: Float::from(100.0),
+            overheat_penalty: Duration::from_secs(1),
+            ..Heat::default()
+        };
+        heat.add_shot(Duration::from_secs(0));
+        assert!(!heat.is_overheated(Duration::from_secs(0)));
+
+        heat.add_shot(Duration::from_secs(0));
+        assert!(heat.is_overheated(Duration::from_millis(500)));
+
+        heat.cool(Duration::from_secs(1), Float::from(1.0));
+        assert!(!heat.is_overheated(Duration::from_secs(1)));
+        assert_eq!(heat.current(), Float::from(90.0));
+    }
+
+    #[test]
+    fn friendly_fire_rules() {
+        use amethyst::ecs::{Builder, World};
+        let mut world = World::new();
+        let owner = world.create_entity().build();
+        let ally = world.create_entity().build();
+        let enemy = world.create_entity().build();
+        let red = Faction {
+            name: "red".to_string(),
+        };
+        let blue = Faction {
+            name: "blue".to_string(),
+        };
+        let can_hit = |policy: FriendlyFire, target, faction| {
+            policy.allows(Some(owner), Some("red"), target, faction)
+        };
+
+        assert!(!can_hit(FriendlyFire::IgnoreOwner, owner, Some(&red)));
+        assert!(can_hit(FriendlyFire::IgnoreOwner, ally, Some(&red)));
+        assert!(!can_hit(FriendlyFire::IgnoreAllies, ally, Some(&red)));
+        assert!(can_hit(FriendlyFire::IgnoreAllies, enemy, Some(&blue)));
+        assert!(can_hit(FriendlyFire::IgnoreAllies, enemy, None));
+        assert!(can_hit(FriendlyFire::All, owner, Some(&red)));
+    }
+
+    #[test]
+    fn explosion_falloff() {
+        let explosion = Explosion {
+            radius: Float::from(10.0),
+            falloff: Falloff::Quadratic,
+            ..Explosion::default()
+        };
+        assert_eq!(explosion.strength_at(Float::from(0.0)), Float::from(1.0));
+        assert_eq!(explosion.strength_at(Float::from(5.0)), Float::from(0.25));
+        assert_eq!(explosion.strength_at(Float::from(10.0)), Float::from(0.0));
+    }
+
+    #[test]
+    fn projectile_expires() {
+        let mut projectile = Projectile {
+            lifetime: Some(Duration::from_secs(1)),
+            max_distance: Some(Float::from(100.0)),
+            ..Projectile::default()
+        };
+        assert!(!projectile.is_expired());
+
+        projectile.age = Duration::from_secs(1);
+        assert!(projectile.is_expired());
+
+        projectile.age = Duration::from_secs(0);
+        projectile.distance_traveled = Float::from(150.0);
+        assert!(projectile.is_expired());
+    }
 }
@@ -0,0 +1,112 @@
+//! Small 2D helpers for working on the XY plane the game is played on
+use amethyst::core::{math::Vector3, Float, Transform};
+
+/// Angle in radians needed to rotate `from` onto `to` around the Z axis. Positive angles turn
+/// counter-clockwise, which matches `Transform::append_rotation_z_axis`.
+pub fn signed_angle(from: &Vector3<Float>, to: &Vector3<Float>) -> Float {
+    let cross = from.x * to.y - from.y * to.x;
+    let dot = from.x * to.x + from.y * to.y;
+    Float::from(cross.as_f32().atan2(dot.as_f32()))
+}
+
+/// A straight line of limited length, such as the path of a hitscan shot
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vector3<Float>,
+    /// Normalized direction the ray points in
+    pub direction: Vector3<Float>,
+    /// How far the ray reaches from its origin
+    pub range: Float,
+}
+
+impl Ray {
+    /// A ray starting at `transform` and pointing the way it faces
+    pub fn forward(transform: &Transform, range: Float) -> Self {
+        Self {
+            origin: *transform.translation(),
+            direction: transform.rotation() * Vector3::y(),
+            range,
+        }
+    }
+
+    /// The point `distance` along the ray
+    pub fn at(&self, distance: Float) -> Vector3<Float> {
+        self.origin + self.direction.scale(distance)
+    }
+}
+
+/// Distance along a ray to the first point touching a circle, or `None` if the ray misses it.
+/// `direction` must be normalized. A ray starting inside the circle hits it at distance zero.
+pub fn ray_circle(
+    origin: &Vector3<Float>,
+    direction: &Vector3<Float>,
+    center: &Vector3<Float>,
+    radius: Float,
+) -> Option<Float> {
+    let offset = origin - center;
+    let b = offset.dot(direction);
+    let c = offset.dot(&offset) - radius * radius;
+    // Starting outside the circle and pointing away from it
+    if c > Float::from(0.0) && b > Float::from(0.0) {
+        return None;
+    }
+    let discriminant = b * b - c;
+    if discriminant < Float::from(0.0) {
+        return None;
+    }
+    let distance = -b - Float::from(discriminant.as_f32().sqrt());
+    if distance < Float::from(0.0) {
+        Some(Float::from(0.0))
+    } else {
+        Some(distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn angle_direction() {
+        let forward = Vector3::y();
+        let left = -Vector3::x();
+        let right = Vector3::x();
+        assert!(signed_angle(&forward, &left) > Float::from(0.0));
+        assert!(signed_angle(&forward, &right) < Float::from(0.0));
+        assert_eq!(signed_angle(&forward, &forward), Float::from(0.0));
+    }
+
+    #[test]
+    fn ray_hits_circle() {
+        let origin = Vector3::zeros();
+        let center = Vector3::new(Float::from(0.0), Float::from(10.0), Float::from(0.0));
+        assert_eq!(
+            ray_circle(&origin, &Vector3::y(), &center, Float::from(2.0)),
+            Some(Float::from(8.0))
+        );
+        assert_eq!(
+            ray_circle(&origin, &-Vector3::y(), &center, Float::from(2.0)),
+            None
+        );
+        assert_eq!(
+            ray_circle(&origin, &Vector3::x(), &center, Float::from(2.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn ray_follows_transform() {
+        let mut transform = Transform::default();
+        transform.prepend_translation(Vector3::new(
+            Float::from(1.0),
+            Float::from(2.0),
+            Float::from(0.0),
+        ));
+        // Facing right
+        transform.append_rotation_z_axis(Float::from(-std::f32::consts::FRAC_PI_2));
+        let ray = Ray::forward(&transform, Float::from(10.0));
+        let end = ray.at(ray.range);
+        assert!((end.x.as_f32() - 11.0).abs() < 1.0e-4);
+        assert!((end.y.as_f32() - 2.0).abs() < 1.0e-4);
+    }
+}
@@ -2,8 +2,10 @@
 
 mod assets;
 mod components;
+mod geometry;
 mod render;
 mod resources;
+mod simulation;
 mod states;
 mod systems;
 
@@ -60,18 +62,14 @@ fn main() -> amethyst::Result<()> {
             "sprite_sheet_processor",
             &[],
         )
-        // Custom systems
-        .with(s::InputSystem::default(), "game_input_system", &[])
+        // Input is latched every frame and used up by the next tick
         .with(
-            s::WeaponSystem::default(),
-            "weapon_system",
-            &["transform_system"],
-        )
-        .with(
-            s::ControllerSystem::default(),
-            "controller_system",
-            &["game_input_system"],
+            s::InputLatchSystem::default(),
+            "input_latch_system",
+            &["input_system"],
         )
+        // Gameplay systems run in fixed ticks from `MainGameState`, see `simulation::Simulation`
+        .with(s::BeamRenderSystem::default(), "beam_render_system", &[])
         // The renderer must be executed on the same thread consecutively, so we initialize it as thread_local
         // which will always execute on the main thread.
         .with_thread_local(RenderingSystem::<DefaultBackend, _>::new(Graph::default()));
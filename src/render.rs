@@ -97,11 +97,12 @@ impl GraphCreator<DefaultBackend> for Graph {
             Some(ClearValue::DepthStencil(ClearDepthStencil(1.0, 0))),
         );
 
-        // Create our first `Subpass`, which contains the DrawFlat2D and DrawUi render groups.
+        // Create our first `Subpass`, which contains the DrawFlat2D and DrawDebugLines render groups.
         // We pass the subpass builder a description of our groups for construction
         let pass = graph_builder.add_node(
             SubpassBuilder::new()
                 .with_group(DrawFlat2DDesc::new().builder()) // Draws sprites
+                .with_group(DrawDebugLinesDesc::new().builder()) // Draws beams
                 .with_color(color)
                 .with_depth_stencil(depth)
                 .into_pass(),
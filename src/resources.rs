@@ -4,8 +4,13 @@ use amethyst::{
         ProgressCounter, RonFormat,
     },
     core::{
-        ecs::{Entity, Read, ReadExpect},
-        Named, Transform,
+        ecs::{
+            world::{EntitiesRes, LazyUpdate},
+            Entity, Read, ReadExpect,
+        },
+        math::Vector3,
+        timing::duration_to_secs,
+        Float, Hidden, Named, Transform,
     },
     derive::PrefabData,
     prelude::*,
@@ -24,9 +29,15 @@ use amethyst::{
 use std::fs::read_dir;
 
 use crate::components as c;
+use crate::components::{
+    weapon::{Explosion, FriendlyFire, Projectile},
+    Faction,
+};
+use rand::{rngs::StdRng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct SResource<V: Asset>(HashMap<String, Handle<V>>);
@@ -68,3 +79,210 @@ where
 
 unsafe impl<V> Send for SResource<V> where V: Asset {}
 unsafe impl<V> Sync for SResource<V> where V: Asset {}
+
+/// A hitscan or beam shot fired during this frame's ticks, from the muzzle to where it stopped.
+/// Segments are drawn where the shot was simulated, while ships are drawn between their last two
+/// ticks, so a beam can sit up to one tick's movement away from the ship that fired it.
+#[derive(Clone, Debug)]
+pub struct BeamSegment {
+    pub start: Vector3<Float>,
+    pub end: Vector3<Float>,
+}
+
+/// Every beam fired during this frame's ticks, so they can be drawn. Cleared by
+/// `simulation::Simulation` before the ticks of each frame that runs any.
+#[derive(Default, Debug)]
+pub struct Beams {
+    pub segments: Vec<BeamSegment>,
+}
+
+/// An explosion waiting to be applied
+#[derive(Clone, Debug)]
+pub struct Detonation {
+    pub position: Vector3<Float>,
+    pub explosion: Explosion,
+    /// Entity that fired the exploding projectile
+    pub owner: Option<Entity>,
+    /// Faction of the entity that fired the exploding projectile
+    pub faction: Option<String>,
+    /// Who the explosion is allowed to hurt
+    pub friendly_fire: FriendlyFire,
+}
+
+impl Detonation {
+    /// Sets off `projectile`'s explosion at `position`, keeping track of who fired it
+    pub fn from_projectile(
+        projectile: &Projectile,
+        explosion: Explosion,
+        position: Vector3<Float>,
+    ) -> Self {
+        Self {
+            position,
+            explosion,
+            owner: projectile.owner,
+            faction: projectile.faction.clone(),
+            friendly_fire: projectile.friendly_fire,
+        }
+    }
+
+    /// Whether the explosion is allowed to hurt `target`
+    pub fn can_hit(&self, target: Entity, target_faction: Option<&Faction>) -> bool {
+        self.friendly_fire.allows(
+            self.owner,
+            self.faction.as_ref().map(String::as_str),
+            target,
+            target_faction,
+        )
+    }
+}
+
+/// Explosions set off this frame
+#[derive(Default, Debug)]
+pub struct Detonations {
+    pub pending: Vec<Detonation>,
+}
+
+/// Hidden projectile entities waiting to be fired again. Reusing them saves creating and deleting
+/// an entity for every shot.
+#[derive(Debug)]
+pub struct ProjectilePool {
+    free: Vec<Entity>,
+    /// Most entities kept for reuse. Projectiles released beyond this are deleted instead.
+    capacity: usize,
+}
+
+impl Default for ProjectilePool {
+    fn default() -> Self {
+        Self::with_capacity(4096)
+    }
+}
+
+impl ProjectilePool {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            free: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Takes a pooled entity that is still alive, if there is one
+    pub fn acquire(&mut self, entities: &EntitiesRes) -> Option<Entity> {
+        while let Some(entity) = self.free.pop() {
+            if entities.is_alive(entity) {
+                return Some(entity);
+            }
+        }
+        None
+    }
+
+    /// Hides a projectile and stops it from moving or hitting anything until it is fired again.
+    /// The components are removed at the end of the frame. If the pool is full the projectile is
+    /// deleted instead.
+    pub fn release(&mut self, entities: &EntitiesRes, lazy: &LazyUpdate, entity: Entity) {
+        if self.free.len() >= self.capacity {
+            entities.delete(entity).expect("Error deleting projectile");
+            return;
+        }
+        lazy.remove::<Projectile>(entity);
+        lazy.remove::<c::Controller>(entity);
+        lazy.remove::<c::Interpolation>(entity);
+        lazy.insert(entity, Hidden);
+        self.free.push(entity);
+    }
+}
+
+/// An entity that was destroyed, and who destroyed it
+#[derive(Clone, Debug)]
+pub struct Kill {
+    pub victim: Entity,
+    /// Whoever last damaged the victim, if anyone
+    pub killer: Option<Entity>,
+}
+
+/// Kills that happened during this frame's ticks. Cleared by `simulation::Simulation` before the
+/// ticks of each frame that runs any.
+#[derive(Default, Debug)]
+pub struct Kills {
+    pub recent: Vec<Kill>,
+}
+
+/// Clock for gameplay systems. The simulation advances in ticks of exactly `step`, however long
+/// frames take, so the same inputs always play out the same way. Has the same accessors as
+/// amethyst's `Time`, but they describe the current tick instead of the current frame.
+#[derive(Debug)]
+pub struct FixedStep {
+    /// Simulated time per tick
+    pub step: Duration,
+    /// Most ticks run in a single frame, so a slow frame can't snowball into ever more ticks
+    pub max_ticks: u32,
+    /// Frame time not yet simulated
+    accumulator: Duration,
+    /// Ticks simulated so far
+    ticks: u32,
+}
+
+impl Default for FixedStep {
+    fn default() -> Self {
+        Self {
+            step: Duration::from_nanos(1_000_000_000 / 60),
+            max_ticks: 8,
+            accumulator: Duration::from_secs(0),
+            ticks: 0,
+        }
+    }
+}
+
+impl FixedStep {
+    /// Adds a frame's worth of time and returns how many ticks should run to catch up
+    pub fn advance(&mut self, frame: Duration) -> u32 {
+        self.accumulator += frame;
+        let mut ticks = 0;
+        while self.accumulator >= self.step && ticks < self.max_ticks {
+            self.accumulator -= self.step;
+            ticks += 1;
+        }
+        // Drop whatever we couldn't catch up on instead of carrying it into the next frame
+        if self.accumulator >= self.step {
+            self.accumulator = self.step;
+        }
+        ticks
+    }
+
+    /// Moves the clock on by one tick
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+    }
+
+    /// Ticks simulated so far
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// How far between the last tick and the next one the current frame is, from 0 to 1
+    pub fn alpha(&self) -> f32 {
+        duration_to_secs(self.accumulator) / duration_to_secs(self.step)
+    }
+
+    pub fn delta_time(&self) -> Duration {
+        self.step
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        duration_to_secs(self.step)
+    }
+
+    /// Simulated time at the end of the current tick
+    pub fn absolute_time(&self) -> Duration {
+        self.step * self.ticks
+    }
+}
+
+/// Random numbers for gameplay. Seeded, so a replay of the same inputs rolls the same numbers.
+#[derive(Debug)]
+pub struct GameRng(pub StdRng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng(StdRng::seed_from_u64(0))
+    }
+}
@@ -0,0 +1,223 @@
+//! Runs gameplay in fixed ticks, separate from the frame rate
+
+use crate::resources::{Beams, FixedStep, Kills};
+use crate::systems as s;
+use amethyst::ecs::{Dispatcher, DispatcherBuilder, RunNow, World};
+use std::time::Duration;
+
+/// Gameplay systems and the fixed-step loop that drives them
+pub struct Simulation<'a, 'b> {
+    dispatcher: Dispatcher<'a, 'b>,
+}
+
+impl<'a, 'b> Simulation<'a, 'b> {
+    /// `input` runs at the start of every tick, before any gameplay system
+    pub fn new<I>(world: &mut World, input: I) -> Self
+    where
+        I: for<'c> RunNow<'c> + 'b,
+    {
+        // Everything runs in order on one thread, so ticks play out identically every time
+        let mut dispatcher = DispatcherBuilder::new()
+            .with_thread_local(input)
+            .with_thread_local(s::SnapshotSystem::default())
+            .with_thread_local(s::WeaponSystem::default())
+            .with_thread_local(s::HomingSystem::default())
+            .with_thread_local(s::ShipStatsSystem::default())
+            .with_thread_local(s::ControllerSystem::default())
+            .with_thread_local(s::ProjectileSystem::default())
+            .with_thread_local(s::CollisionSystem::default())
+            .with_thread_local(s::ExplosionSystem::default())
+            .with_thread_local(s::HealthSystem::default())
+            .build();
+        dispatcher.setup(&mut world.res);
+        Self { dispatcher }
+    }
+
+    /// Runs as many ticks as `frame` calls for, then places everything between its last two
+    /// ticks for drawing. Returns the number of ticks run.
+    pub fn run_frame(&mut self, world: &mut World, frame: Duration) -> u32 {
+        let ticks = world.write_resource::<FixedStep>().advance(frame);
+        s::RestoreSystem.run_now(&world.res);
+        // Beams and kills are kept for the whole frame, so nothing from an earlier tick is lost.
+        // Without any ticks there is nothing new, and last frame's are kept.
+        if ticks > 0 {
+            world.write_resource::<Beams>().segments.clear();
+            world.write_resource::<Kills>().recent.clear();
+        }
+        for _ in 0..ticks {
+            world.write_resource::<FixedStep>().tick();
+            self.dispatcher.dispatch(&world.res);
+            // Entities spawned this tick have to exist for the next one
+            world.maintain();
+        }
+        s::InterpolationSystem.run_now(&world.res);
+        ticks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{
+        weapon::{Projectile, Spread, Weapon, WeaponManager},
+        Controller, Engine, FlightModel, Health, Hull, Player,
+    };
+    use crate::systems::input::{Action, InputLatch};
+    use amethyst::core::{math::Vector3, Float, Hidden, Transform};
+    use amethyst::ecs::{Builder, Join, Read, ReadStorage, System, WriteStorage};
+    use amethyst::renderer::SpriteRender;
+
+    /// Plays back the same inputs for every tick, whatever the frame rate
+    struct ScriptedInput;
+
+    impl<'a> System<'a> for ScriptedInput {
+        type SystemData = (
+            Read<'a, FixedStep>,
+            ReadStorage<'a, Player>,
+            WriteStorage<'a, Controller>,
+            WriteStorage<'a, WeaponManager>,
+        );
+
+        fn run(&mut self, (step, players, mut controllers, mut managers): Self::SystemData) {
+            let tick = step.ticks();
+            for (_, controller, manager) in (&players, &mut controllers, &mut managers).join() {
+                controller.thrust_control = Float::from(if tick % 60 < 40 { 1.0 } else { 0.0 });
+                controller.rotation_control = Float::from(if tick % 50 < 25 { 1.0 } else { -1.0 });
+                controller.strafe_control = Float::from(if tick % 90 < 30 { 1.0 } else { 0.0 });
+                manager.set_trigger(tick % 40 < 25);
+            }
+        }
+    }
+
+    /// Simulates `ticks` ticks in frames of `frame` and returns the bits of every entity's
+    /// simulated position and rotation
+    fn simulate(frame: Duration, ticks: u32) -> Vec<Vec<u32>> {
+        let mut world = World::new();
+        world.register::<Hidden>();
+        world.register::<SpriteRender>();
+        let mut simulation = Simulation::new(&mut world, ScriptedInput);
+
+        let mut manager = WeaponManager::default();
+        let mut weapon = Weapon::default();
+        weapon.pattern.count = 3;
+        weapon.pattern.spread = Float::from(0.5);
+        weapon.pattern.spread_kind = Spread::Random;
+        manager.weapons_mut().push(weapon);
+        world
+            .create_entity()
+            .with(Player::default())
+            .with(Transform::default())
+            .with(Controller {
+                flight_model: FlightModel::Assisted {
+                    strength: Float::from(3.0),
+                },
+                ..Controller::default()
+            })
+            .with(Engine {
+                angular_acceleration: Float::from(40.0),
+                ..Engine::default()
+            })
+            .with(Hull::default())
+            .with(manager)
+            .build();
+
+        let mut target = Transform::default();
+        target.prepend_translation(Vector3::new(
+            Float::from(0.0),
+            Float::from(400.0),
+            Float::from(0.0),
+        ));
+        world
+            .create_entity()
+            .with(target)
+            .with(Health::default())
+            .build();
+
+        let mut run = 0;
+        while run < ticks {
+            run += simulation.run_frame(&mut world, frame);
+        }
+        assert_eq!(run, ticks);
+
+        // Compare the simulated transforms, not the interpolated ones drawn this frame
+        s::RestoreSystem.run_now(&world.res);
+        let transforms = world.read_storage::<Transform>();
+        let simulated = (&transforms)
+            .join()
+            .map(|transform| {
+                let rotation = transform.rotation().coords;
+                transform
+                    .translation()
+                    .iter()
+                    .chain(rotation.iter())
+                    .map(|value| value.as_f32().to_bits())
+                    .collect()
+            })
+            .collect();
+        simulated
+    }
+
+    #[test]
+    fn ticks_are_independent_of_frame_rate() {
+        let step = FixedStep::default().step;
+        let slow = simulate(step * 3, 240);
+        let fast = simulate(Duration::from_millis(7), 240);
+        // Make sure there was more going on than the two ships sitting still
+        assert!(slow.len() > 2);
+        assert_eq!(slow, fast);
+    }
+
+    #[test]
+    fn taps_between_ticks_reach_the_next_tick() {
+        let mut world = World::new();
+        world.register::<Hidden>();
+        world.register::<SpriteRender>();
+        let mut simulation = Simulation::new(&mut world, s::InputSystem::default());
+
+        let mut manager = WeaponManager::default();
+        manager.weapons_mut().push(Weapon::default());
+        let mut second = Weapon::default();
+        second.projectile.damage = Float::from(42.0);
+        second.recoil = Duration::from_secs(0);
+        manager.weapons_mut().push(second);
+        let ship = world
+            .create_entity()
+            .with(Player::default())
+            .with(Transform::default())
+            .with(Controller::default())
+            .with(manager)
+            .build();
+        let current_damage = |world: &World| {
+            let managers = world.read_storage::<WeaponManager>();
+            let weapon = managers.get(ship).unwrap().current_weapon().unwrap();
+            weapon.projectile.damage.as_f32()
+        };
+
+        // Fire and next weapon are pressed and let go again in frames too short for a tick
+        let short = Duration::from_millis(1);
+        for down in &[true, false] {
+            {
+                let mut latch = world.write_resource::<InputLatch>();
+                latch.set_action(Action::Fire, *down);
+                latch.set_action(Action::NextWeapon, *down);
+            }
+            assert_eq!(simulation.run_frame(&mut world, short), 0);
+        }
+
+        // The next tick switches weapons once and fires the new one
+        let step = world.read_resource::<FixedStep>().step;
+        assert_eq!(simulation.run_frame(&mut world, step), 1);
+        assert_eq!(current_damage(&world), 42.0);
+        let fired: Vec<f32> = world
+            .read_storage::<Projectile>()
+            .join()
+            .map(|projectile| projectile.damage.as_f32())
+            .collect();
+        assert_eq!(fired, vec![42.0]);
+
+        // Each tap is only used once
+        assert_eq!(simulation.run_frame(&mut world, step), 1);
+        assert_eq!(current_damage(&world), 42.0);
+        assert_eq!(world.read_storage::<Projectile>().join().count(), 1);
+    }
+}
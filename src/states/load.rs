@@ -9,7 +9,7 @@ use amethyst::{
     ecs::{ReadExpect, Read},
 };
 
-use crate::assets::prefab::{initialize_prefabs, update_prefabs};
+use crate::assets::prefab::{initialize_prefabs, resolve_projectile_sprites, update_prefabs};
 use crate::resources::SResource;
 use crate::states::main::MainGameState;
 use amethyst::utils::application_root_dir;
@@ -67,8 +67,10 @@ impl SimpleState for LoadState {
                 // If so, reset our progress, and updae prefabs
                 self.prefab_progress = None;
                 update_prefabs(&mut data.world);
+                resolve_projectile_sprites(&mut data.world)
+                    .expect("Error resolving projectile sprites");
                 // Create a new main state now that our resource is full of prefabs
-                return Trans::Switch(Box::new(MainGameState {}));
+                return Trans::Switch(Box::new(MainGameState::default()));
             }
         }
         Trans::None
@@ -1,11 +1,13 @@
 use crate::assets::prefab::EntityPrefabData;
 use crate::assets::prefab::EntityPrefabs;
 use crate::components as c;
+use crate::simulation::Simulation;
+use crate::systems::InputSystem;
 use amethyst::prelude::*;
 use amethyst::{
     assets::{Handle, Prefab},
     core::math::{Point3, Vector3},
-    core::{Float, Transform},
+    core::{Float, Time, Transform},
     ecs::{Entities, Entity, Read, ReadExpect, WriteStorage},
 };
 use specs_physics::{
@@ -13,17 +15,30 @@ use specs_physics::{
     colliders::Shape,
     physics_dispatcher, PhysicsBodyBuilder, PhysicsColliderBuilder,
 };
-pub struct MainGameState {}
+#[derive(Default)]
+pub struct MainGameState {
+    /// Gameplay systems, which run in fixed ticks instead of once per frame
+    simulation: Option<Simulation<'static, 'static>>,
+}
 
 impl SimpleState for MainGameState {
     fn on_start(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
         let mut world = data.world;
         world.register::<c::weapon::Projectile>();
+        self.simulation = Some(Simulation::new(world, InputSystem::default()));
 
         create_with_prefab(world, "game::camera");
         create_with_prefab(world, "game::light");
         create_with_prefab(world, "game::player");
     }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let frame = data.world.read_resource::<Time>().delta_time();
+        if let Some(simulation) = self.simulation.as_mut() {
+            simulation.run_frame(data.world, frame);
+        }
+        Trans::None
+    }
 }
 
 pub fn get_prefab(world: &mut World, path: &str) -> Handle<Prefab<EntityPrefabData>> {
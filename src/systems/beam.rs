@@ -0,0 +1,30 @@
+use crate::resources::Beams;
+use amethyst::core::math::Point3;
+use amethyst::ecs::{Read, System, Write};
+use amethyst::renderer::{debug_drawing::DebugLines, palette::Srgba};
+
+/// Draws the beams fired this frame as debug lines
+#[derive(Default, Debug)]
+pub struct BeamRenderSystem;
+
+impl<'a> System<'a> for BeamRenderSystem {
+    type SystemData = (Read<'a, Beams>, Write<'a, DebugLines>);
+
+    fn run(&mut self, (beams, mut lines): Self::SystemData) {
+        for segment in &beams.segments {
+            lines.draw_line(
+                Point3::new(
+                    segment.start.x.as_f32(),
+                    segment.start.y.as_f32(),
+                    segment.start.z.as_f32(),
+                ),
+                Point3::new(
+                    segment.end.x.as_f32(),
+                    segment.end.y.as_f32(),
+                    segment.end.z.as_f32(),
+                ),
+                Srgba::new(1.0, 0.3, 0.3, 1.0),
+            );
+        }
+    }
+}
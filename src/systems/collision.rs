@@ -0,0 +1,278 @@
+use crate::components::{weapon::Projectile, Faction, Health};
+use crate::geometry::{ray_circle, Ray};
+use crate::resources::{Detonation, Detonations, ProjectilePool};
+use amethyst::core::{Float, Transform};
+use amethyst::ecs::{
+    world::{EntitiesRes, LazyUpdate},
+    Entities, Entity, Join, Read, ReadStorage, System, Write, WriteStorage,
+};
+
+/// Finds the closest living entity with `Health` along a ray, skipping anything `can_hit` rejects
+/// and anything the ray starts inside of, such as the ship firing it. Returns the entity and how
+/// far along the ray it was hit.
+pub fn raycast(
+    entities: &EntitiesRes,
+    healths: &WriteStorage<Health>,
+    transforms: &ReadStorage<Transform>,
+    can_hit: impl Fn(Entity) -> bool,
+    ray: &Ray,
+) -> Option<(Entity, Float)> {
+    (entities, healths, transforms)
+        .join()
+        .filter(|(target, health, transform)| {
+            let inside = (transform.translation() - ray.origin).magnitude() < health.radius;
+            can_hit(*target) && !health.is_dead() && !inside
+        })
+        .filter_map(|(target, health, transform)| {
+            ray_circle(
+                &ray.origin,
+                &ray.direction,
+                transform.translation(),
+                health.radius,
+            )
+            .filter(|distance| *distance <= ray.range)
+            .map(|distance| (target, distance))
+        })
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+/// Checks projectiles against everything with `Health` and applies damage on a hit. Also sets off
+/// proximity explosions.
+#[derive(Default, Debug)]
+pub struct CollisionSystem;
+
+impl<'a> System<'a> for CollisionSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Projectile>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Faction>,
+        WriteStorage<'a, Health>,
+        Write<'a, Detonations>,
+        Write<'a, ProjectilePool>,
+        Read<'a, LazyUpdate>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut projectiles,
+            transforms,
+            factions,
+            mut healths,
+            mut detonations,
+            mut pool,
+            lazy,
+        ): Self::SystemData,
+    ) {
+        for (projectile_entity, projectile, projectile_transform) in
+            (&entities, &mut projectiles, &transforms).join()
+        {
+            // Shots start inside the ship that fired them, so they can't hit it until they are clear
+            if !projectile.left_owner {
+                let owner = projectile
+                    .owner
+                    .and_then(|owner| Some((healths.get(owner)?, transforms.get(owner)?)));
+                projectile.left_owner = owner.map_or(true, |(health, transform)| {
+                    let distance =
+                        (transform.translation() - projectile_transform.translation()).magnitude();
+                    distance > health.radius + projectile.radius
+                });
+            }
+
+            // Projectiles that haven't armed yet pass through everything. Expired ones were already
+            // returned to the pool this frame.
+            if !projectile.is_armed() || projectile.is_expired() {
+                continue;
+            }
+
+            // Find the first living target within `reach` that the projectile is allowed to hurt
+            let within = |reach: Float| {
+                (&entities, &healths, &transforms)
+                    .join()
+                    .find(|(target, health, transform)| {
+                        let distance = (transform.translation()
+                            - projectile_transform.translation())
+                        .magnitude();
+                        projectile.can_hit(*target, factions.get(*target))
+                            && !health.is_dead()
+                            && distance <= health.radius + reach
+                    })
+                    .map(|(target, _, _)| target)
+            };
+
+            let hit = within(projectile.radius);
+            let explosion = projectile.explosion.as_ref();
+            let proximity = explosion
+                .and_then(|explosion| explosion.proximity)
+                .map_or(false, |proximity| within(proximity).is_some());
+
+            if let Some(target) = hit {
+                if let Some(health) = healths.get_mut(target) {
+                    health.damage(projectile.damage, projectile.owner);
+                }
+            }
+
+            if hit.is_some() || proximity {
+                if let Some(explosion) = explosion {
+                    if proximity || explosion.on_hit {
+                        detonations.pending.push(Detonation::from_projectile(
+                            projectile,
+                            explosion.clone(),
+                            *projectile_transform.translation(),
+                        ));
+                    }
+                }
+                pool.release(&entities, &lazy, projectile_entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{weapon::FriendlyFire, Controller, Interpolation};
+    use amethyst::core::{math::Vector3, Hidden};
+    use amethyst::ecs::{Builder, RunNow, World};
+    use std::time::Duration;
+
+    fn create_world() -> (World, CollisionSystem) {
+        let mut world = World::new();
+        let mut system = CollisionSystem;
+        System::setup(&mut system, &mut world.res);
+        world.register::<Controller>();
+        world.register::<Interpolation>();
+        world.register::<Hidden>();
+        (world, system)
+    }
+
+    fn at(x: f32, y: f32) -> Transform {
+        let mut transform = Transform::default();
+        transform.prepend_translation(Vector3::new(
+            Float::from(x),
+            Float::from(y),
+            Float::from(0.0),
+        ));
+        transform
+    }
+
+    fn ship(world: &mut World, x: f32, y: f32, faction: &str) -> Entity {
+        world
+            .create_entity()
+            .with(at(x, y))
+            .with(Health::default())
+            .with(Faction {
+                name: faction.to_string(),
+            })
+            .build()
+    }
+
+    /// A shot fired by `owner` that hits everything, including its own side
+    fn reckless_shot(owner: Entity) -> Projectile {
+        Projectile {
+            friendly_fire: FriendlyFire::All,
+            arm_time: Duration::from_secs(0),
+            owner: Some(owner),
+            faction: Some("red".to_string()),
+            ..Projectile::default()
+        }
+    }
+
+    fn damage_taken(world: &World, entity: Entity) -> f32 {
+        let health = world.read_storage::<Health>();
+        let health = health.get(entity).unwrap();
+        (health.max - health.current()).as_f32()
+    }
+
+    #[test]
+    fn projectiles_damage_what_they_hit_and_are_removed() {
+        let (mut world, mut system) = create_world();
+        let owner = ship(&mut world, 0.0, 0.0, "red");
+        let enemy = ship(&mut world, 0.0, 200.0, "blue");
+        let shot = Projectile {
+            owner: Some(owner),
+            faction: Some("red".to_string()),
+            arm_time: Duration::from_secs(0),
+            ..Projectile::default()
+        };
+        let miss = world
+            .create_entity()
+            .with(at(100.0, 100.0))
+            .with(shot.clone())
+            .build();
+        let hit = world
+            .create_entity()
+            .with(at(0.0, 170.0))
+            .with(shot)
+            .build();
+
+        system.run_now(&world.res);
+        world.maintain();
+        assert_eq!(damage_taken(&world, enemy), 10.0);
+        assert_eq!(damage_taken(&world, owner), 0.0);
+        assert_eq!(
+            world
+                .read_storage::<Health>()
+                .get(enemy)
+                .unwrap()
+                .last_attacker(),
+            Some(owner)
+        );
+
+        // The projectile that hit is put away, the one that missed keeps flying
+        let projectiles = world.read_storage::<Projectile>();
+        assert!(projectiles.get(hit).is_none());
+        assert!(world.read_storage::<Hidden>().get(hit).is_some());
+        assert!(projectiles.get(miss).is_some());
+    }
+
+    #[test]
+    fn reckless_projectiles_spare_their_owner_until_clear() {
+        let (mut world, mut system) = create_world();
+        let owner = ship(&mut world, 0.0, 0.0, "red");
+        let ally = ship(&mut world, 0.0, 100.0, "red");
+        let shot = world
+            .create_entity()
+            .with(at(0.0, 0.0))
+            .with(reckless_shot(owner))
+            .build();
+
+        system.run_now(&world.res);
+        world.maintain();
+        assert_eq!(damage_taken(&world, owner), 0.0);
+
+        *world.write_storage::<Transform>().get_mut(shot).unwrap() = at(0.0, 100.0);
+        system.run_now(&world.res);
+        world.maintain();
+        assert_eq!(damage_taken(&world, ally), 10.0);
+        assert_eq!(damage_taken(&world, owner), 0.0);
+    }
+
+    #[test]
+    fn reckless_rays_spare_their_owner() {
+        let (mut world, _) = create_world();
+        let owner = ship(&mut world, 0.0, 0.0, "red");
+        let ally = ship(&mut world, 0.0, 100.0, "red");
+        let ray = Ray::forward(&at(0.0, 0.0), Float::from(500.0));
+
+        // Even a shot that may hit its owner passes out of the ship it starts inside
+        let fresh = reckless_shot(owner);
+        let cleared = Projectile {
+            left_owner: true,
+            ..fresh.clone()
+        };
+        let factions = world.read_storage::<Faction>();
+        for shot in &[fresh, cleared] {
+            let hit = raycast(
+                &world.entities(),
+                &world.write_storage::<Health>(),
+                &world.read_storage::<Transform>(),
+                |target| shot.can_hit(target, factions.get(target)),
+                &ray,
+            );
+            assert_eq!(hit, Some((ally, Float::from(68.0))));
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use crate::components::Controller;
-use amethyst::core::Time;
+use crate::components::{Controller, Engine};
+use crate::resources::FixedStep;
 use amethyst::core::{math::Vector3, Float, Transform};
 use amethyst::ecs::{Join, Read, System, WriteStorage};
 
@@ -10,26 +10,34 @@ impl<'a> System<'a> for ControllerSystem {
     type SystemData = (
         WriteStorage<'a, Controller>,
         WriteStorage<'a, Transform>,
-        Read<'a, Time>,
+        WriteStorage<'a, Engine>,
+        Read<'a, FixedStep>,
     );
 
-    fn run(&mut self, (mut controllers, mut transforms, time): Self::SystemData) {
-        for (controller, transform) in (&mut controllers, &mut transforms).join() {
-            // rotate based on unit points
-            transform.append_rotation_z_axis(
-                // This will orient the rotation direction correctly
-                controller.rotation_control *
-                // Multiply by our turn speed, which is just a multiplier.
-                Float::from(controller.turn_speed) *
-                // Finally, multiply everything by our delta to keep consistent across framerates
-                Float::from(time.delta_seconds()),
-            );
+    fn run(&mut self, (mut controllers, mut transforms, mut engines, time): Self::SystemData) {
+        for (controller, transform, engine) in
+            (&mut controllers, &mut transforms, (&mut engines).maybe()).join()
+        {
+            // Afterburners raise acceleration and top speed for as long as their energy lasts
+            let mut traction = controller.traction;
+            let mut max_speed = controller.max_speed;
+            if let Some(boost) = engine.and_then(|engine| engine.boost.as_mut()) {
+                let delta_seconds = Float::from(time.delta_seconds());
+                if boost.update(controller.boosting, time.absolute_time(), delta_seconds) {
+                    traction *= boost.traction_multiplier;
+                    max_speed *= boost.speed_multiplier;
+                }
+            }
+
+            // Turn with whatever angular momentum the ship has built up
+            let turn = controller.turn(Float::from(time.delta_seconds()));
+            transform.append_rotation_z_axis(turn);
 
             // If our input is 0, we're not changing our velocity.
             if controller.thrust_control != Float::from(0.) {
                 // Calculate impulse
                 let added_magnitude = Vector3::y().scale(
-                    controller.traction
+                    traction
                         * Float::from(time.delta_seconds())
                         * controller.thrust_control,
                 );
@@ -47,13 +55,33 @@ impl<'a> System<'a> for ControllerSystem {
 
                 // Limit velocity
                 let magnitude = controller.velocity.magnitude();
-                if magnitude > controller.max_speed {
-                    controller.velocity /= magnitude / controller.max_speed;
+                if magnitude > max_speed {
+                    controller.velocity /= magnitude / max_speed;
                 } else if magnitude < controller.min_speed {
                     controller.velocity = transform.rotation() * Vector3::y().scale(controller.min_speed);
                 }
             }
 
+            // Lateral thrusters push along the ship's local X
+            if controller.strafe_control != Float::from(0.) {
+                let added_magnitude = Vector3::x().scale(
+                    controller.strafe_traction
+                        * Float::from(time.delta_seconds())
+                        * controller.strafe_control,
+                );
+                controller.velocity += transform.rotation() * added_magnitude;
+
+                // Strafing can't get us past our top speed either
+                let magnitude = controller.velocity.magnitude();
+                if magnitude > max_speed {
+                    controller.velocity /= magnitude / max_speed;
+                }
+            }
+
+            // Drag or flight assist change the velocity even without thrust
+            let forward = transform.rotation() * Vector3::y();
+            controller.apply_flight_model(&forward, Float::from(time.delta_seconds()));
+
             // Apply existing velocity and rotational velocity.
             let movement = controller.velocity.scale(Float::from(time.delta_seconds()));
 
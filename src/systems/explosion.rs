@@ -0,0 +1,61 @@
+use crate::components::{Controller, Faction, Health};
+use crate::resources::Detonations;
+use amethyst::core::{Float, Transform};
+use amethyst::ecs::{Entities, Join, ReadStorage, System, Write, WriteStorage};
+
+/// Applies every explosion set off this frame to the things caught in its blast
+#[derive(Default, Debug)]
+pub struct ExplosionSystem;
+
+impl<'a> System<'a> for ExplosionSystem {
+    type SystemData = (
+        Entities<'a>,
+        Write<'a, Detonations>,
+        WriteStorage<'a, Health>,
+        WriteStorage<'a, Controller>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Faction>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut detonations, mut healths, mut controllers, transforms, factions): Self::SystemData,
+    ) {
+        for detonation in detonations.pending.drain(..) {
+            let explosion = &detonation.explosion;
+            for (target, health, transform, controller) in (
+                &entities,
+                &mut healths,
+                &transforms,
+                (&mut controllers).maybe(),
+            )
+                .join()
+            {
+                if !detonation.can_hit(target, factions.get(target)) || health.is_dead() {
+                    continue;
+                }
+
+                // Measure to the target's edge so big ships can't hide their center
+                let offset = transform.translation() - detonation.position;
+                let mut distance = offset.magnitude() - health.radius;
+                if distance < Float::from(0.0) {
+                    distance = Float::from(0.0);
+                }
+                let strength = explosion.strength_at(distance);
+                if strength <= Float::from(0.0) {
+                    continue;
+                }
+
+                health.damage(explosion.damage * strength, detonation.owner);
+
+                // Push the target away from the center of the blast
+                if let Some(controller) = controller {
+                    if offset.magnitude() > Float::from(0.0) {
+                        controller.velocity +=
+                            offset.normalize().scale(explosion.knockback * strength);
+                    }
+                }
+            }
+        }
+    }
+}
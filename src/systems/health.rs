@@ -0,0 +1,63 @@
+use crate::components::Health;
+use crate::resources::{Kill, Kills};
+use amethyst::ecs::{Entities, Join, ReadStorage, System, Write};
+
+/// Destroys entities that have run out of health and credits whoever destroyed them
+#[derive(Default, Debug)]
+pub struct HealthSystem;
+
+impl<'a> System<'a> for HealthSystem {
+    type SystemData = (Entities<'a>, ReadStorage<'a, Health>, Write<'a, Kills>);
+
+    fn run(&mut self, (entities, healths, mut kills): Self::SystemData) {
+        for (entity, health) in (&entities, &healths).join() {
+            if health.is_dead() {
+                kills.recent.push(Kill {
+                    victim: entity,
+                    killer: health.last_attacker(),
+                });
+                entities
+                    .delete(entity)
+                    .expect("Error deleting destroyed entity");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::core::Float;
+    use amethyst::ecs::{Builder, RunNow, World};
+
+    #[test]
+    fn destroyed_entities_are_deleted_and_credited() {
+        let mut world = World::new();
+        let mut system = HealthSystem;
+        System::setup(&mut system, &mut world.res);
+        let attacker = world.create_entity().with(Health::default()).build();
+        let victim = world.create_entity().with(Health::default()).build();
+        let survivor = world.create_entity().with(Health::default()).build();
+        {
+            let mut healths = world.write_storage::<Health>();
+            healths
+                .get_mut(victim)
+                .unwrap()
+                .damage(Float::from(100.0), Some(attacker));
+            healths
+                .get_mut(survivor)
+                .unwrap()
+                .damage(Float::from(99.0), Some(attacker));
+        }
+
+        system.run_now(&world.res);
+        world.maintain();
+
+        assert!(!world.is_alive(victim));
+        assert!(world.is_alive(survivor));
+        let kills = &world.read_resource::<Kills>().recent;
+        assert_eq!(kills.len(), 1);
+        assert_eq!(kills[0].victim, victim);
+        assert_eq!(kills[0].killer, Some(attacker));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::components::{
+    weapon::{Projectile, Retarget},
+    Controller, Faction, Health,
+};
+use crate::geometry::signed_angle;
+use crate::resources::FixedStep;
+use amethyst::core::{math::Vector3, Float, Transform};
+use amethyst::ecs::{Entities, Entity, Join, Read, ReadStorage, System, WriteStorage};
+
+/// Steers homing projectiles toward their target by driving their `Controller::rotation_control`
+#[derive(Default, Debug)]
+pub struct HomingSystem;
+
+impl<'a> System<'a> for HomingSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Projectile>,
+        WriteStorage<'a, Controller>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Health>,
+        ReadStorage<'a, Faction>,
+        Read<'a, FixedStep>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut projectiles, mut controllers, transforms, healths, factions, time): Self::SystemData,
+    ) {
+        for (projectile, controller, transform) in
+            (&mut projectiles, &mut controllers, &transforms).join()
+        {
+            let owner = projectile.owner;
+            let faction = projectile.faction.clone();
+            let friendly_fire = projectile.friendly_fire;
+            let homing = match projectile.homing.as_mut() {
+                Some(homing) => homing,
+                None => continue,
+            };
+
+            let (range, cone, retarget) = (homing.range, homing.cone, homing.retarget);
+            let position = transform.translation();
+            let forward = transform.rotation() * Vector3::y();
+            // Distance and turn angle to a potential target, if it is something we can chase
+            let bearing = |target: Entity| -> Option<(Float, Float)> {
+                // Never chase our own ship, even if the projectile could hurt it
+                let faction = faction.as_ref().map(String::as_str);
+                if Some(target) == owner
+                    || !friendly_fire.allows(owner, faction, target, factions.get(target))
+                    || !entities.is_alive(target)
+                {
+                    return None;
+                }
+                let health = healths.get(target)?;
+                if health.is_dead() {
+                    return None;
+                }
+                let offset = transforms.get(target)?.translation() - position;
+                let distance = offset.magnitude();
+                if distance > range {
+                    return None;
+                }
+                Some((distance, signed_angle(&forward, &offset)))
+            };
+            let in_cone = |angle: Float| angle.as_f32().abs() <= cone.as_f32();
+
+            // Decide whether our current target is still worth chasing
+            let current = homing.target.and_then(|target| {
+                bearing(target).and_then(|(_, angle)| match retarget {
+                    Retarget::Never => Some(angle),
+                    Retarget::OnLoss | Retarget::Nearest if in_cone(angle) => Some(angle),
+                    _ => None,
+                })
+            });
+
+            let search = match retarget {
+                Retarget::Never => !homing.acquired,
+                Retarget::OnLoss => current.is_none(),
+                Retarget::Nearest => true,
+            };
+
+            let mut angle = current;
+            if search {
+                let nearest = (&entities, &healths)
+                    .join()
+                    .filter_map(|(target, _)| {
+                        bearing(target)
+                            .filter(|(_, angle)| in_cone(*angle))
+                            .map(|(distance, angle)| (target, distance, angle))
+                    })
+                    .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+                if let Some((target, _, target_angle)) = nearest {
+                    homing.target = Some(target);
+                    homing.acquired = true;
+                    angle = Some(target_angle);
+                } else if current.is_none() {
+                    homing.target = None;
+                }
+            }
+
+            controller.rotation_control = match angle {
+                // Turn as hard as needed to face the target this frame, without overshooting it
+                Some(angle) => {
+                    let max_turn = homing.turn_rate.as_f32() * time.delta_seconds();
+                    if max_turn > 0.0 {
+                        Float::from((angle.as_f32() / max_turn).max(-1.0).min(1.0))
+                    } else {
+                        Float::from(0.0)
+                    }
+                }
+                None => Float::from(0.0),
+            };
+        }
+    }
+}
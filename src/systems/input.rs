@@ -1,23 +1,39 @@
-use crate::components::{weapon::WeaponManager, Controller, Player};
+use crate::components::{
+    weapon::{Direction, WeaponManager},
+    Controller, Player,
+};
 use amethyst::core::{
     math::{Point2, Unit},
     Float, Transform,
 };
-use amethyst::ecs::{Join, Read, ReadStorage, System, WriteStorage};
+use amethyst::ecs::{Join, Read, System, Write, WriteStorage};
 use amethyst::input::BindingTypes;
 use amethyst::input::InputHandler;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 #[derive(Debug, Hash, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Axis {
     Horizontal,
     Vertical,
+    /// Lateral thrust, positive to the right
+    Strafe,
 }
 
 #[derive(Debug, Hash, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Action {
     Fire,
+    /// Fire every weapon in the fire group at this index in the `WeaponManager`
+    FireGroup(usize),
+    NextWeapon,
+    PreviousWeapon,
+    /// Equip the weapon at this index in the `WeaponManager`
+    SelectSlot(usize),
+    /// Turn flight assist on or off
+    ToggleFlightAssist,
+    /// Fire the afterburner while held
+    Boost,
 }
 
 impl fmt::Display for Axis {
@@ -38,6 +54,75 @@ impl BindingTypes for GameBindings {
     type Action = Action;
 }
 
+/// Player input gathered once per frame. Presses are kept until a tick has seen them, so a tap that
+/// starts and ends between two ticks still reaches the next one.
+#[derive(Default, Debug)]
+pub struct InputLatch {
+    /// Latest value of every axis
+    axes: HashMap<Axis, f32>,
+    /// Actions held down as of the latest frame
+    down: HashSet<Action>,
+    /// Actions that went down since the last tick, even if they have been let go since
+    pressed: HashSet<Action>,
+}
+
+impl InputLatch {
+    pub fn set_axis(&mut self, axis: Axis, value: f32) {
+        self.axes.insert(axis, value);
+    }
+
+    /// Records whether `action` is held down this frame
+    pub fn set_action(&mut self, action: Action, down: bool) {
+        if down {
+            if self.down.insert(action.clone()) {
+                self.pressed.insert(action);
+            }
+        } else {
+            self.down.remove(&action);
+        }
+    }
+
+    pub fn axis(&self, axis: &Axis) -> f32 {
+        self.axes.get(axis).cloned().unwrap_or(0.0)
+    }
+
+    /// Whether `action` is held, or was tapped since the last tick
+    pub fn is_down(&self, action: &Action) -> bool {
+        self.down.contains(action) || self.pressed.contains(action)
+    }
+
+    /// Whether `action` went down since the last tick
+    pub fn just_pressed(&self, action: &Action) -> bool {
+        self.pressed.contains(action)
+    }
+
+    /// Forgets presses once a tick has acted on them
+    pub fn consume(&mut self) {
+        self.pressed.clear();
+    }
+}
+
+/// Copies the state of every bound axis and action into the `InputLatch` once per frame
+#[derive(Default, Debug)]
+pub struct InputLatchSystem;
+
+impl<'a> System<'a> for InputLatchSystem {
+    type SystemData = (Read<'a, InputHandler<GameBindings>>, Write<'a, InputLatch>);
+
+    fn run(&mut self, (input, mut latch): Self::SystemData) {
+        for axis in input.bindings.axes() {
+            latch.set_axis(axis.clone(), input.axis_value(axis).unwrap_or(0.0));
+        }
+        for action in input.bindings.actions() {
+            latch.set_action(
+                action.clone(),
+                input.action_is_down(action).unwrap_or(false),
+            );
+        }
+    }
+}
+
+/// Hands the latched input to the player's ship at the start of every tick
 #[derive(Default, Debug)]
 pub struct InputSystem;
 
@@ -46,21 +131,53 @@ impl<'a> System<'a> for InputSystem {
         WriteStorage<'a, Player>,
         WriteStorage<'a, Controller>,
         WriteStorage<'a, WeaponManager>,
-        Read<'a, InputHandler<GameBindings>>,
+        Write<'a, InputLatch>,
     );
 
-    fn run(&mut self, (mut players, mut controllers, mut managers, input): Self::SystemData) {
+    fn run(&mut self, (mut players, mut controllers, mut managers, mut input): Self::SystemData) {
+        // One-shot actions only trigger once per press
+        let toggle_assist = input.just_pressed(&Action::ToggleFlightAssist);
         // Loop through all players and assign direction
         for (controller, player) in (&mut controllers, &mut players).join() {
-            controller.rotation_control = Float::from(input.axis_value(&Axis::Horizontal).unwrap());
-            controller.thrust_control = Float::from(input.axis_value(&Axis::Vertical).unwrap());
+            controller.rotation_control = Float::from(input.axis(&Axis::Horizontal));
+            controller.thrust_control = Float::from(input.axis(&Axis::Vertical));
+            controller.strafe_control = Float::from(input.axis(&Axis::Strafe));
+            controller.boosting = input.is_down(&Action::Boost);
+            if toggle_assist {
+                controller.flight_assist = !controller.flight_assist;
+            }
         }
 
+        // Weapon switching should only happen once per key press
+        let next_weapon = input.just_pressed(&Action::NextWeapon);
+        let previous_weapon = input.just_pressed(&Action::PreviousWeapon);
+        let selected_slot = input
+            .pressed
+            .iter()
+            .filter_map(|action| match action {
+                Action::SelectSlot(slot) => Some(*slot),
+                _ => None,
+            })
+            .max();
+
         // loop through all weapons systems and assign firing states
         for (_, manager) in (&mut players, &mut managers).join() {
-            manager.wants_to_fire = input
-                .action_is_down(&Action::Fire)
-                .expect("Error reading action");
+            if next_weapon {
+                manager.cycle(Direction::Forward);
+            }
+            if previous_weapon {
+                manager.cycle(Direction::Backward);
+            }
+            if let Some(slot) = selected_slot {
+                manager.set_index(slot);
+            }
+
+            manager.set_trigger(input.is_down(&Action::Fire));
+            for (index, group) in manager.fire_groups_mut().iter_mut().enumerate() {
+                group.set_trigger(input.is_down(&Action::FireGroup(index)));
+            }
         }
+
+        input.consume();
     }
 }
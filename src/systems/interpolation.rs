@@ -0,0 +1,86 @@
+use crate::components::{Controller, Interpolation};
+use crate::resources::FixedStep;
+use amethyst::core::{Float, Hidden, Transform};
+use amethyst::ecs::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+
+/// Remembers where every moving entity was before a tick. Runs first in every tick.
+#[derive(Default, Debug)]
+pub struct SnapshotSystem;
+
+impl<'a> System<'a> for SnapshotSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Controller>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Hidden>,
+        WriteStorage<'a, Interpolation>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, controllers, transforms, hiddens, mut interpolations): Self::SystemData,
+    ) {
+        // Pooled projectiles are skipped, so they don't streak across the screen when reused
+        for (entity, _, transform, _) in (&entities, &controllers, &transforms, !&hiddens).join() {
+            match interpolations.get_mut(entity) {
+                Some(interpolation) => interpolation.previous = transform.clone(),
+                None => {
+                    interpolations
+                        .insert(
+                            entity,
+                            Interpolation {
+                                previous: transform.clone(),
+                                current: transform.clone(),
+                            },
+                        )
+                        .expect("Error inserting interpolation");
+                }
+            }
+        }
+    }
+}
+
+/// Puts back the simulated transforms that `InterpolationSystem` replaced for drawing. Runs before
+/// the ticks of a frame.
+#[derive(Default, Debug)]
+pub struct RestoreSystem;
+
+impl<'a> System<'a> for RestoreSystem {
+    type SystemData = (WriteStorage<'a, Transform>, ReadStorage<'a, Interpolation>);
+
+    fn run(&mut self, (mut transforms, interpolations): Self::SystemData) {
+        for (transform, interpolation) in (&mut transforms, &interpolations).join() {
+            *transform = interpolation.current.clone();
+        }
+    }
+}
+
+/// Moves every entity to where it would be between its last two ticks at this point in the frame.
+/// Runs after the ticks of a frame.
+#[derive(Default, Debug)]
+pub struct InterpolationSystem;
+
+impl<'a> System<'a> for InterpolationSystem {
+    type SystemData = (
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Interpolation>,
+        Read<'a, FixedStep>,
+    );
+
+    fn run(&mut self, (mut transforms, mut interpolations, step): Self::SystemData) {
+        let alpha = Float::from(step.alpha());
+        for (transform, interpolation) in (&mut transforms, &mut interpolations).join() {
+            interpolation.current = transform.clone();
+            let (previous, current) = (&interpolation.previous, &interpolation.current);
+
+            let translation = previous.translation().lerp(current.translation(), alpha);
+            // Turning exactly halfway around has no shortest path, so just show the latest rotation
+            let rotation = previous
+                .rotation()
+                .try_slerp(current.rotation(), alpha, Float::from(1.0e-6))
+                .unwrap_or_else(|| *current.rotation());
+            *transform.translation_mut() = translation;
+            *transform.rotation_mut() = rotation;
+        }
+    }
+}
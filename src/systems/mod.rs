@@ -1,7 +1,23 @@
+mod beam;
+mod collision;
 mod controller;
+mod explosion;
+mod health;
+mod homing;
 pub mod input;
+mod interpolation;
+mod projectile;
+mod ship_stats;
 mod weapon;
 
+pub use beam::BeamRenderSystem;
+pub use collision::CollisionSystem;
 pub use controller::ControllerSystem;
-pub use input::InputSystem;
+pub use explosion::ExplosionSystem;
+pub use health::HealthSystem;
+pub use homing::HomingSystem;
+pub use input::{InputLatchSystem, InputSystem};
+pub use interpolation::{InterpolationSystem, RestoreSystem, SnapshotSystem};
+pub use projectile::ProjectileSystem;
+pub use ship_stats::ShipStatsSystem;
 pub use weapon::WeaponSystem;
@@ -0,0 +1,177 @@
+use crate::components::{weapon::Projectile, Controller};
+use crate::resources::{Detonation, Detonations, FixedStep, ProjectilePool};
+use amethyst::core::{Float, Transform};
+use amethyst::ecs::{
+    world::LazyUpdate, Entities, Join, Read, ReadStorage, System, Write, WriteStorage,
+};
+
+/// Ages every projectile and returns the ones that have expired to the pool
+#[derive(Default, Debug)]
+pub struct ProjectileSystem;
+
+impl<'a> System<'a> for ProjectileSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Projectile>,
+        ReadStorage<'a, Controller>,
+        ReadStorage<'a, Transform>,
+        Write<'a, Detonations>,
+        Write<'a, ProjectilePool>,
+        Read<'a, LazyUpdate>,
+        Read<'a, FixedStep>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut projectiles,
+            controllers,
+            transforms,
+            mut detonations,
+            mut pool,
+            lazy,
+            time,
+        ): Self::SystemData,
+    ) {
+        for (entity, projectile, controller, transform) in
+            (&entities, &mut projectiles, &controllers, &transforms).join()
+        {
+            projectile.age += time.delta_time();
+            projectile.distance_traveled +=
+                controller.velocity.magnitude() * Float::from(time.delta_seconds());
+
+            if projectile.is_expired() {
+                if let Some(explosion) = projectile.explosion.as_ref().filter(|e| e.on_expire) {
+                    detonations.pending.push(Detonation::from_projectile(
+                        projectile,
+                        explosion.clone(),
+                        *transform.translation(),
+                    ));
+                }
+                pool.release(&entities, &lazy, entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::Interpolation;
+    use crate::systems::weapon::spawn_projectile;
+    use amethyst::core::{math::Vector3, Hidden};
+    use amethyst::ecs::{world::EntitiesRes, Entity, RunNow, World};
+    use amethyst::renderer::SpriteRender;
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    fn create_world(pool: ProjectilePool) -> (World, ProjectileSystem) {
+        let mut world = World::new();
+        let mut system = ProjectileSystem;
+        System::setup(&mut system, &mut world.res);
+        world.register::<Hidden>();
+        world.register::<Interpolation>();
+        world.register::<SpriteRender>();
+        world.add_resource(pool);
+        (world, system)
+    }
+
+    /// Fires a projectile the same way `WeaponSystem` does
+    fn fire(world: &World, controller: Controller, transform: Transform, projectile: Projectile) {
+        let entities = world.read_resource::<EntitiesRes>();
+        let lazy = world.read_resource::<LazyUpdate>();
+        let mut pool = world.write_resource::<ProjectilePool>();
+        spawn_projectile(
+            &lazy, &entities, &mut pool, controller, transform, projectile,
+        );
+    }
+
+    /// Fires `per_frame` bullets every frame for `frames` frames and lets them expire. Returns how
+    /// many distinct entities were used.
+    fn run_bullets(pool: ProjectilePool, frames: usize, per_frame: usize) -> usize {
+        let (mut world, mut system) = create_world(pool);
+        let projectile = Projectile {
+            lifetime: Some(Duration::from_millis(500)),
+            ..Projectile::default()
+        };
+        let mut used = HashSet::<Entity>::new();
+        for _ in 0..frames {
+            for _ in 0..per_frame {
+                fire(
+                    &world,
+                    Controller::default(),
+                    Transform::default(),
+                    projectile.clone(),
+                );
+            }
+            world.maintain();
+            used.extend(
+                (&*world.entities(), &world.read_storage::<Projectile>())
+                    .join()
+                    .map(|(entity, _)| entity),
+            );
+            system.run_now(&world.res);
+            world.maintain();
+        }
+        used.len()
+    }
+
+    #[test]
+    fn reused_projectiles_are_fired_again() {
+        let (mut world, mut system) = create_world(ProjectilePool::default());
+        let expired = Projectile {
+            lifetime: Some(Duration::from_secs(0)),
+            ..Projectile::default()
+        };
+        fire(&world, Controller::default(), Transform::default(), expired);
+        world.maintain();
+        system.run_now(&world.res);
+        world.maintain();
+
+        let entity = (&*world.entities(), &world.read_storage::<Hidden>())
+            .join()
+            .map(|(entity, _)| entity)
+            .next()
+            .expect("Expired projectile wasn't hidden");
+        assert!(world.read_storage::<Projectile>().get(entity).is_none());
+        assert!(world.read_storage::<Controller>().get(entity).is_none());
+
+        let velocity = Vector3::new(Float::from(0.0), Float::from(250.0), Float::from(0.0));
+        let mut transform = Transform::default();
+        transform.prepend_translation(velocity);
+        let projectile = Projectile {
+            damage: Float::from(42.0),
+            ..Projectile::default()
+        };
+        fire(
+            &world,
+            Controller {
+                velocity,
+                ..Controller::default()
+            },
+            transform,
+            projectile,
+        );
+        world.maintain();
+
+        // The same entity comes back visible, with everything from the new shot
+        assert_eq!((&*world.entities()).join().count(), 1);
+        assert!(world.read_storage::<Hidden>().get(entity).is_none());
+        assert!(world.read_storage::<SpriteRender>().get(entity).is_none());
+        let projectiles = world.read_storage::<Projectile>();
+        assert_eq!(projectiles.get(entity).unwrap().damage, Float::from(42.0));
+        let controllers = world.read_storage::<Controller>();
+        assert_eq!(controllers.get(entity).unwrap().velocity, velocity);
+        let transforms = world.read_storage::<Transform>();
+        assert_eq!(*transforms.get(entity).unwrap().translation(), velocity);
+    }
+
+    #[test]
+    fn expired_projectiles_are_reused() {
+        let pooled = run_bullets(ProjectilePool::default(), 60, 10);
+        let unpooled = run_bullets(ProjectilePool::with_capacity(0), 60, 10);
+        assert_eq!(unpooled, 60 * 10);
+        assert!(pooled < unpooled);
+    }
+}
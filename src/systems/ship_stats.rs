@@ -0,0 +1,21 @@
+use crate::components::{Controller, Engine, Hull};
+use amethyst::ecs::{Join, ReadStorage, System, WriteStorage};
+
+/// Keeps each ship's `Controller` in line with its `Engine` and `Hull`, so swapping either changes
+/// how the ship flies
+#[derive(Default, Debug)]
+pub struct ShipStatsSystem;
+
+impl<'a> System<'a> for ShipStatsSystem {
+    type SystemData = (
+        ReadStorage<'a, Engine>,
+        ReadStorage<'a, Hull>,
+        WriteStorage<'a, Controller>,
+    );
+
+    fn run(&mut self, (engines, hulls, mut controllers): Self::SystemData) {
+        for (engine, hull, controller) in (&engines, &hulls, &mut controllers).join() {
+            controller.set_from_ship(engine, hull);
+        }
+    }
+}
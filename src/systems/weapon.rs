@@ -1,13 +1,22 @@
-use crate::components as c;
+use super::collision::raycast;
+use crate::components::{
+    self as c,
+    weapon::{Charge, WeaponKind},
+};
+use crate::geometry::Ray;
+use crate::resources::{BeamSegment, Beams, FixedStep, GameRng, ProjectilePool};
 use amethyst::core::{
     math::{Unit, Vector3},
-    Float, Time, Transform,
+    timing::duration_to_secs,
+    Float, Hidden, Transform,
 };
-use amethyst::{
-    ecs::{prelude::*, world::LazyUpdate, Entities, Join, Read, System, WriteStorage},
-    renderer::{SpriteRender},
+use amethyst::ecs::{
+    prelude::*,
+    world::{EntitiesRes, LazyUpdate},
+    Entities, Join, Read, ReadStorage, System, Write, WriteStorage,
 };
-use crate::assets::ResourceCollection;
+use amethyst::renderer::SpriteRender;
+use std::time::Duration;
 
 #[derive(Default, Debug)]
 pub struct WeaponSystem;
@@ -18,48 +27,439 @@ impl<'a> System<'a> for WeaponSystem {
         Read<'a, LazyUpdate>,
         WriteStorage<'a, c::weapon::WeaponManager>,
         WriteStorage<'a, c::Controller>,
-        WriteStorage<'a, Transform>,
-        Read<'a, Time>,
-        Read<'a, ResourceCollection>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, c::Hardpoints>,
+        ReadStorage<'a, c::Hull>,
+        WriteStorage<'a, c::Inventory>,
+        WriteStorage<'a, c::Health>,
+        ReadStorage<'a, c::Faction>,
+        Write<'a, Beams>,
+        Write<'a, ProjectilePool>,
+        Write<'a, GameRng>,
+        Read<'a, FixedStep>,
     );
 
     fn run(
         &mut self,
-        (entities, lazy, mut managers, mut controllers, mut transforms, time, res): Self::SystemData,
+        (
+            entities,
+            lazy,
+            mut managers,
+            mut controllers,
+            transforms,
+            hardpoints,
+            hulls,
+            mut inventories,
+            mut healths,
+            factions,
+            mut beams,
+            mut pool,
+            mut rng,
+            time,
+        ): Self::SystemData,
     ) {
         let controllers_looper = &mut controllers;
         // Loop through all players and assign direction to their controller
-        for (manager, controller, transform) in
-            (&mut managers, controllers_looper, &mut transforms).join()
+        for (entity, manager, controller, transform, mut inventory) in (
+            &entities,
+            &mut managers,
+            controllers_looper,
+            &transforms,
+            (&mut inventories).maybe(),
+        )
+            .join()
         {
-            // We only care if the weapons manager wants to fire. Otherwise, do nothing.
-            if manager.wants_to_fire {
-                let current_time = time.absolute_time();
-
-                // check if we can actually fire our weapon
-                let poss_weapon = manager.current_weapon_mut();
-                if let Some(weapon) = poss_weapon {
-                    if current_time >= weapon.last_fired + weapon.recoil {
-                        // reset our recoil timer
-                        weapon.last_fired = current_time;
-                        // create an entity and assign it a clone of our weapon's `Projectile` component
-                        let projectile = &weapon.projectile;
-
-                        // What sprite to apply to the projectile entity
-                        let sprite_render = SpriteRender {
-                            sprite_sheet: res.projectile_sheet.clone().unwrap(),
-                            sprite_number: 0, // First sprite
-                        };
-
-                        lazy.create_entity(&entities)
-                            .with(projectile.clone())
-                            .with(transform.clone())
-                            .with(controller.clone().set_from_projectile(&projectile))
-                            .with(sprite_render)
-                            .build();
+            let current_time = time.absolute_time();
+            let delta_seconds = Float::from(time.delta_seconds());
+
+            // Every weapon cools down and reloads, even when it isn't the one in use
+            manager.update_weapons(
+                current_time,
+                delta_seconds,
+                inventory.as_mut().map(|i| &mut **i),
+            );
+
+            let ship_hardpoints = hardpoints.get(entity);
+            // Ships without a hull are as heavy as the default one
+            let hull = hulls.get(entity).cloned().unwrap_or_default();
+            // Kickback is applied once every shot is out, so the ship's path this tick and the
+            // velocity its shots inherit don't depend on how many of them came before
+            let mut kickback = Vector3::zeros();
+            for (index, trigger) in manager.triggered_weapons() {
+                let wants_to_fire = trigger.is_down();
+                let weapon = &mut manager.weapons_mut()[index];
+                // Weapons fire from the hardpoint they are mounted on
+                let muzzle = muzzle_transform(
+                    transform,
+                    weapon
+                        .hardpoint
+                        .as_ref()
+                        .and_then(|name| ship_hardpoints.and_then(|points| points.get(name))),
+                );
+
+                // Charge weapons fire when the trigger is let go, everything else fires while it is held
+                let charge = weapon.charge.clone();
+                let (firing, charge_level) = match weapon.charge.as_mut() {
+                    Some(charge) => {
+                        let level = charge.update(trigger, delta_seconds);
+                        (level.is_some(), level)
+                    }
+                    None => (wants_to_fire, None),
+                };
+
+                // We only care if the weapons manager wants to fire. Otherwise, do nothing.
+                if !firing {
+                    continue;
+                }
+
+                // Everything this weapon fires remembers who fired it and which side they are on
+                let mut source = weapon.projectile.clone();
+                source.owner = Some(entity);
+                source.faction = factions.get(entity).map(|faction| faction.name.clone());
+
+                if let WeaponKind::Beam {
+                    range,
+                    damage_per_second,
+                } = weapon.kind
+                {
+                    if weapon.is_ready(current_time) {
+                        if let Some(charge) = weapon.charge.as_mut() {
+                            charge.take();
+                        }
+                        // Beams fire every frame, but only use up ammo and heat once per recoil
+                        if current_time >= weapon.last_fired + weapon.recoil {
+                            weapon.record_shot(current_time);
+                        }
+                        beams.segments.push(fire_ray(
+                            &entities,
+                            &mut healths,
+                            &transforms,
+                            &factions,
+                            &source,
+                            Ray::forward(&muzzle, range),
+                            damage_per_second * delta_seconds,
+                        ));
+                    }
+                    continue;
+                }
+
+                // Fire every shot that came due this tick, even if that is more than one, so fire
+                // rate doesn't depend on the tick rate. A weapon that was idle starts from this tick
+                // instead of catching up on shots it never fired.
+                let tick_start = current_time
+                    .checked_sub(time.delta_time())
+                    .unwrap_or_default();
+                let mut shot_time = std::cmp::max(weapon.last_fired + weapon.recoil, tick_start);
+                while shot_time <= current_time && weapon.is_ready(shot_time) {
+                    weapon.record_shot(shot_time);
+                    // A released charge is only used up once it is actually fired
+                    if let Some(charge) = weapon.charge.as_mut() {
+                        charge.take();
+                    }
+
+                    // The ship hasn't moved yet this tick, so move the muzzle along to where it is
+                    // at the exact time of the shot
+                    let early = Float::from(duration_to_secs(shot_time - tick_start));
+                    let late = current_time - shot_time;
+                    let late_seconds = Float::from(duration_to_secs(late));
+                    let mut shot_muzzle = muzzle.clone();
+                    shot_muzzle.prepend_translation(controller.velocity.scale(early));
+
+                    match weapon.kind.clone() {
+                        WeaponKind::Projectile => {
+                            // create an entity and assign it a clone of our weapon's `Projectile` component
+                            let mut projectile = source.clone();
+                            projectile.age = late;
+
+                            // Charged shots hit harder, fly faster and grow bigger
+                            let mut size = Float::from(1.0);
+                            if let (Some(charge), Some(level)) = (&charge, charge_level) {
+                                charge.apply(&mut projectile, level);
+                                size = Charge::scale(size, charge.size_scale, level);
+                            }
+
+                            // Every barrel fires the full spread of projectiles
+                            let angles = weapon.pattern.angles(&mut rng.0);
+                            for offset in weapon.pattern.next_barrels() {
+                                for angle in &angles {
+                                    // Move to the barrel, then turn towards this projectile's angle
+                                    let mut shot_transform = shot_muzzle.clone();
+                                    shot_transform.prepend_translation(
+                                        shot_muzzle.rotation() * Vector3::x().scale(offset),
+                                    );
+                                    shot_transform.append_rotation_z_axis(*angle);
+                                    *shot_transform.scale_mut() *= size;
+
+                                    let shot_controller = controller.clone().set_from_projectile(
+                                        &projectile,
+                                        shot_transform.rotation(),
+                                    );
+                                    // Late shots have already been flying for a little while
+                                    let launch = shot_controller.velocity
+                                        + shot_transform.rotation()
+                                            * Vector3::y().scale(shot_controller.start_speed);
+                                    shot_transform.prepend_translation(launch.scale(late_seconds));
+
+                                    spawn_projectile(
+                                        &lazy,
+                                        &entities,
+                                        &mut pool,
+                                        shot_controller,
+                                        shot_transform,
+                                        projectile.clone(),
+                                    );
+                                }
+                            }
+                        }
+                        WeaponKind::Mine { drop_distance } => {
+                            let mut projectile = source.clone();
+                            projectile.age = late;
+
+                            // Mines are left behind the ship instead of fired ahead of it
+                            let mut mine_transform = shot_muzzle.clone();
+                            mine_transform.prepend_translation(
+                                shot_muzzle.rotation() * -Vector3::y().scale(drop_distance),
+                            );
+                            let mine_controller = controller
+                                .clone()
+                                .set_from_mine(&projectile, mine_transform.rotation());
+                            mine_transform
+                                .prepend_translation(mine_controller.velocity.scale(late_seconds));
+                            spawn_projectile(
+                                &lazy,
+                                &entities,
+                                &mut pool,
+                                mine_controller,
+                                mine_transform,
+                                projectile,
+                            );
+                        }
+                        WeaponKind::Hitscan { range, damage } => {
+                            let damage = match (&charge, charge_level) {
+                                (Some(charge), Some(level)) => {
+                                    Charge::scale(damage, charge.damage_scale, level)
+                                }
+                                _ => damage,
+                            };
+                            beams.segments.push(fire_ray(
+                                &entities,
+                                &mut healths,
+                                &transforms,
+                                &factions,
+                                &source,
+                                Ray::forward(&shot_muzzle, range),
+                                damage,
+                            ));
+                        }
+                        WeaponKind::Beam { .. } => {}
+                    }
+
+                    // Every shot shoves the ship away from where it is aimed
+                    let kick = hull.velocity_change(weapon.kickback);
+                    kickback -= (shot_muzzle.rotation() * Vector3::y()).scale(kick);
+
+                    // A charge only fires one shot, and a weapon without recoil fires once per tick
+                    if charge.is_some() || weapon.recoil == Duration::from_secs(0) {
+                        break;
                     }
+                    shot_time += weapon.recoil;
                 }
             }
+            controller.velocity += kickback;
+        }
+    }
+}
+
+/// Fires a projectile at the end of the frame, reusing a pooled entity when there is one
+pub(super) fn spawn_projectile(
+    lazy: &LazyUpdate,
+    entities: &EntitiesRes,
+    pool: &mut ProjectilePool,
+    controller: c::Controller,
+    transform: Transform,
+    projectile: c::weapon::Projectile,
+) {
+    if let Some(entity) = pool.acquire(entities) {
+        lazy.remove::<Hidden>(entity);
+        lazy.insert(entity, controller);
+        lazy.insert(entity, transform);
+        // The old sprite may belong to a different weapon
+        match &projectile.sprite.render {
+            Some(sprite_render) => lazy.insert(entity, sprite_render.clone()),
+            None => lazy.remove::<SpriteRender>(entity),
+        }
+        lazy.insert(entity, projectile);
+        return;
+    }
+
+    let mut builder = lazy
+        .create_entity(entities)
+        .with(controller)
+        .with(transform);
+    // The sprite is looked up when prefabs load
+    if let Some(sprite_render) = &projectile.sprite.render {
+        builder = builder.with(sprite_render.clone());
+    }
+    builder.with(projectile).build();
+}
+
+/// Where a weapon fires from: the ship's own transform, moved and turned to its hardpoint
+fn muzzle_transform(ship: &Transform, hardpoint: Option<&c::Hardpoint>) -> Transform {
+    let mut muzzle = ship.clone();
+    if let Some(hardpoint) = hardpoint {
+        let (x, y) = hardpoint.offset;
+        muzzle.prepend_translation(ship.rotation() * Vector3::new(x, y, Float::from(0.0)));
+        muzzle.append_rotation_z_axis(hardpoint.angle);
+    }
+    muzzle
+}
+
+/// Damages the first thing along `ray` that `source` may hurt. Returns the beam so it can be drawn.
+fn fire_ray(
+    entities: &EntitiesRes,
+    healths: &mut WriteStorage<c::Health>,
+    transforms: &ReadStorage<Transform>,
+    factions: &ReadStorage<c::Faction>,
+    source: &c::weapon::Projectile,
+    ray: Ray,
+    damage: Float,
+) -> BeamSegment {
+    let hit = raycast(
+        entities,
+        healths,
+        transforms,
+        |target| source.can_hit(target, factions.get(target)),
+        &ray,
+    );
+
+    // Beams stop at whatever they hit, otherwise they reach their full range
+    let distance = match hit {
+        Some((target, distance)) => {
+            if let Some(health) = healths.get_mut(target) {
+                health.damage(damage, source.owner);
+            }
+            distance
+        }
+        None => ray.range,
+    };
+
+    BeamSegment {
+        start: ray.origin,
+        end: ray.at(distance),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::weapon::{Inheritance, Launch, Weapon, WeaponManager};
+    use amethyst::ecs::{Builder, RunNow, World};
+
+    #[test]
+    fn rapid_fire_spreads_shots_along_the_ship_path() {
+        let mut world = World::new();
+        let mut system = WeaponSystem::default();
+        System::setup(&mut system, &mut world.res);
+        world.write_resource::<FixedStep>().tick();
+        let step = world.read_resource::<FixedStep>().step;
+
+        // Four shots per tick that stay exactly where they are fired
+        let mut weapon = Weapon::default();
+        weapon.recoil = step / 4;
+        weapon.projectile.initial_speed = Float::from(0.0);
+        weapon.projectile.inheritance = Inheritance::None;
+        weapon.projectile.launch = Launch::InitialSpeed;
+        weapon.kickback = Float::from(1000.0);
+        let mut manager = WeaponManager::default();
+        manager.weapons_mut().push(weapon);
+        manager.set_trigger(true);
+
+        let speed = 600.0;
+        let ship = world
+            .create_entity()
+            .with(Transform::default())
+            .with(c::Controller {
+                velocity: Vector3::new(Float::from(speed), Float::from(0.0), Float::from(0.0)),
+                ..c::Controller::default()
+            })
+            .with(manager)
+            .build();
+
+        system.run_now(&world.res);
+        world.maintain();
+
+        // The ship is pushed back by every shot, but only once they have all been fired
+        let kick = c::Hull::default()
+            .velocity_change(Float::from(1000.0))
+            .as_f32();
+        let velocity = world
+            .read_storage::<c::Controller>()
+            .get(ship)
+            .unwrap()
+            .velocity;
+        assert!((velocity.x.as_f32() - speed).abs() < 1.0e-3);
+        assert!((velocity.y.as_f32() + kick * 4.0).abs() < 1.0e-3);
+
+        let projectiles = world.read_storage::<c::weapon::Projectile>();
+        let transforms = world.read_storage::<Transform>();
+        let positions: Vec<(f32, f32)> = (&projectiles, &transforms)
+            .join()
+            .map(|(_, transform)| {
+                let translation = transform.translation();
+                (translation.x.as_f32(), translation.y.as_f32())
+            })
+            .collect();
+        assert_eq!(positions.len(), 4);
+        // Each shot is where the ship was when it was fired, the last one at the end of the tick
+        let spacing = speed * duration_to_secs(step / 4);
+        for (shot, (x, y)) in positions.iter().enumerate() {
+            assert!((x - spacing * (shot + 1) as f32).abs() < 1.0e-3);
+            assert!(y.abs() < 1.0e-3);
         }
     }
+
+    #[test]
+    fn charge_released_during_recoil_fires_once_ready() {
+        let mut world = World::new();
+        let mut system = WeaponSystem::default();
+        System::setup(&mut system, &mut world.res);
+        let step = world.read_resource::<FixedStep>().step;
+
+        // The weapon has only just fired, so it is still recoiling when the charge is let go
+        let mut weapon = Weapon::default();
+        weapon.recoil = step * 3;
+        weapon.last_fired = step;
+        weapon.charge = Some(Charge {
+            time: step * 4,
+            ..Charge::default()
+        });
+        let mut manager = WeaponManager::default();
+        manager.weapons_mut().push(weapon);
+        let ship = world
+            .create_entity()
+            .with(Transform::default())
+            .with(c::Controller::default())
+            .with(manager)
+            .build();
+
+        // Charge for a tick, then let go and wait for the recoil to end
+        let mut fired = vec![];
+        for held in &[true, false, false, false] {
+            world.write_resource::<FixedStep>().tick();
+            world
+                .write_storage::<WeaponManager>()
+                .get_mut(ship)
+                .unwrap()
+                .set_trigger(*held);
+            system.run_now(&world.res);
+            world.maintain();
+            fired.push(world.read_storage::<c::weapon::Projectile>().join().count());
+        }
+        assert_eq!(fired, vec![0, 0, 0, 1]);
+
+        // The shot keeps the quarter charge it was released with
+        let projectiles = world.read_storage::<c::weapon::Projectile>();
+        let damage = projectiles.join().next().unwrap().damage.as_f32();
+        assert!((damage - 15.0).abs() < 1.0e-3);
+    }
 }